@@ -0,0 +1,493 @@
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use chrono::{Days, NaiveDate};
+
+use crate::models::{ChartScope, DBState, Status, StatusChange};
+
+// how far back a chart of every story goes when no range is given
+const DEFAULT_RANGE: Days = Days::new(29);
+
+// partial blocks for the top of a bar, in eighths of a row
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ChartKind {
+    // remaining work falling towards zero
+    #[default]
+    Burndown,
+    // completed work rising towards the total
+    Burnup,
+}
+
+impl ChartKind {
+    pub fn toggled(&self) -> Self {
+        match self {
+            ChartKind::Burndown => ChartKind::Burnup,
+            ChartKind::Burnup => ChartKind::Burndown,
+        }
+    }
+}
+
+// What the chart command draws
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ChartOptions {
+    pub scope: ChartScope,
+    pub kind: ChartKind,
+    // the default range for the scope when not set, see get_default_range()
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    // write the series as CSV instead of drawing it
+    pub csv: bool,
+    // draw with # and . for terminals without Unicode
+    pub ascii: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Work {
+    // stories that existed on the day
+    pub total: usize,
+    // stories that were RESOLVED or CLOSED at the end of the day
+    pub completed: usize,
+}
+
+impl Work {
+    pub fn remaining(&self) -> usize {
+        self.total - self.completed
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChartPoint {
+    pub date: NaiveDate,
+    // None for days after today
+    pub work: Option<Work>,
+    // where the work would be if it went at a steady pace from the first day to the last
+    pub ideal: f64,
+}
+
+// A sprint runs from its start to its end date. Other charts end today, an epic's chart starts
+// with the first change to its stories and a chart of every story covers the last 30 days
+pub fn get_default_range(
+    db_state: &DBState,
+    scope: ChartScope,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let fallback_start = today.checked_sub_days(DEFAULT_RANGE).unwrap_or(today);
+    match scope {
+        ChartScope::Sprint(sprint_id) => {
+            let sprint = db_state
+                .sprints
+                .get(&sprint_id)
+                .ok_or_else(|| anyhow!("could not find sprint with id: {}", sprint_id))?;
+            Ok((sprint.start_date, sprint.end_date))
+        }
+        ChartScope::Epic(_) => {
+            let story_ids = get_story_ids(db_state, scope)?;
+            let start = db_state
+                .status_changes
+                .iter()
+                .filter(|change| story_ids.contains(&change.story_id))
+                .map(|change| change.date)
+                .min()
+                .unwrap_or(fallback_start);
+            Ok((start.min(today), today))
+        }
+        ChartScope::All => Ok((fallback_start, today)),
+    }
+}
+
+// One point per day from start to end, counting the stories in scope from the status change log.
+// Stories with no log entries are counted with their current status on every day
+pub fn get_series(
+    db_state: &DBState,
+    scope: ChartScope,
+    start: NaiveDate,
+    end: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<ChartPoint>> {
+    if end < start {
+        return Err(anyhow!(
+            "the chart ends before it starts: {} to {}",
+            start,
+            end
+        ));
+    }
+    let story_ids = get_story_ids(db_state, scope)?;
+    let histories: Vec<(Status, Vec<&StatusChange>)> = story_ids
+        .iter()
+        .filter_map(|id| {
+            let story = db_state.stories.get(id)?;
+            let mut changes: Vec<_> = db_state
+                .status_changes
+                .iter()
+                .filter(|change| change.story_id == *id)
+                .collect();
+            changes.sort_by_key(|change| change.date);
+            Some((story.status.clone(), changes))
+        })
+        .collect();
+
+    let mut points: Vec<ChartPoint> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| {
+            let work = (date <= today).then(|| {
+                let statuses: Vec<Status> = histories
+                    .iter()
+                    .filter_map(|(status, changes)| get_status_on(status, changes, date))
+                    .collect();
+                Work {
+                    total: statuses.len(),
+                    completed: statuses.iter().filter(|status| status.is_done()).count(),
+                }
+            });
+            ChartPoint {
+                date,
+                work,
+                ideal: 0.0,
+            }
+        })
+        .collect();
+
+    set_ideal(&mut points);
+    Ok(points)
+}
+
+// the ids of the stories in scope, whether or not they still exist
+fn get_story_ids(db_state: &DBState, scope: ChartScope) -> Result<Vec<u32>> {
+    match scope {
+        ChartScope::All => Ok(db_state.stories.keys().copied().collect()),
+        ChartScope::Epic(epic_id) => db_state
+            .epics
+            .get(&epic_id)
+            .map(|epic| epic.stories.clone())
+            .ok_or_else(|| anyhow!("could not find epic with id: {}", epic_id)),
+        ChartScope::Sprint(sprint_id) => db_state
+            .sprints
+            .get(&sprint_id)
+            .map(|sprint| sprint.stories.clone())
+            .ok_or_else(|| anyhow!("could not find sprint with id: {}", sprint_id)),
+    }
+}
+
+// The story's status at the end of date, None if it was created later. Before its first logged
+// change a story had the status that change was from
+fn get_status_on(current: &Status, changes: &[&StatusChange], date: NaiveDate) -> Option<Status> {
+    let Some(first) = changes.first() else {
+        return Some(current.clone());
+    };
+    match changes.iter().rev().find(|change| change.date <= date) {
+        Some(change) => Some(change.to.clone()),
+        None => first.from.clone(),
+    }
+}
+
+// the ideal remaining work goes from the first day's remaining work to nothing on the last day
+fn set_ideal(points: &mut [ChartPoint]) {
+    let Some(first) = points.first().and_then(|point| point.work) else {
+        return;
+    };
+    let days = points.len().saturating_sub(1).max(1) as f64;
+    for (i, point) in points.iter_mut().enumerate() {
+        point.ideal = first.remaining() as f64 * (days - i as f64) / days;
+    }
+}
+
+// date, total, completed, remaining and ideal remaining for each day. The counts are left empty
+// for days after today
+pub fn write_series_csv(points: &[ChartPoint], writer: impl Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["date", "total", "completed", "remaining", "ideal"])?;
+    for point in points {
+        let counts = match point.work {
+            Some(work) => [work.total, work.completed, work.remaining()].map(|n| n.to_string()),
+            None => Default::default(),
+        };
+        writer.write_record([
+            point.date.to_string().as_str(),
+            &counts[0],
+            &counts[1],
+            &counts[2],
+            &format!("{:.2}", point.ideal),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// The chart as lines of text, width columns wide including the axis, with height rows of bars.
+// Days are stretched or sampled to fill the width. Bars use Unicode blocks, or # when ascii is
+// set, and the ideal line is drawn over them
+pub fn draw_chart(
+    points: &[ChartPoint],
+    kind: ChartKind,
+    width: usize,
+    height: usize,
+    ascii: bool,
+) -> Vec<String> {
+    let height = height.max(1);
+    let (Some(first_work), Some(last_work)) = (
+        points.first().and_then(|point| point.work),
+        points.iter().rev().find_map(|point| point.work),
+    ) else {
+        return vec!["no data to chart".to_owned()];
+    };
+    let days = points.len().saturating_sub(1).max(1) as f64;
+    let max_total = points
+        .iter()
+        .filter_map(|point| point.work.map(|work| work.total))
+        .max()
+        .unwrap_or(0);
+    let max_value = max_total.max(1) as f64;
+    let label_width = max_total.to_string().len();
+    let plot_width = width.saturating_sub(label_width + 1).max(1);
+    let columns = get_columns(points.len(), plot_width);
+
+    let (bar_char, ideal_char, scope_char) = if ascii {
+        ('#', '.', '-')
+    } else {
+        ('█', '·', '─')
+    };
+    // a value as a height in eighths of a row
+    let to_eighths = |value: f64| (value / max_value * (height * 8) as f64).round() as usize;
+
+    let mut grid = vec![vec![' '; columns.len()]; height];
+    for (column, index) in columns.iter().enumerate() {
+        let point = &points[*index];
+        let (bar, ideal) = match kind {
+            ChartKind::Burndown => (point.work.map(|work| work.remaining()), point.ideal),
+            // from the first day's completed work to the total on the last day with any work
+            ChartKind::Burnup => (
+                point.work.map(|work| work.completed),
+                first_work.completed as f64
+                    + (last_work.total - first_work.completed) as f64 * *index as f64 / days,
+            ),
+        };
+        if let Some(bar) = bar {
+            let eighths = to_eighths(bar as f64);
+            for (row, cells) in grid.iter_mut().enumerate() {
+                let filled = eighths.saturating_sub((height - 1 - row) * 8).min(8);
+                cells[column] = match ascii {
+                    true if filled >= 4 => bar_char,
+                    true => ' ',
+                    false => BLOCKS[filled],
+                };
+            }
+        }
+        if let (ChartKind::Burnup, Some(work)) = (kind, point.work) {
+            set_line_cell(&mut grid, column, to_eighths(work.total as f64), scope_char);
+        }
+        set_line_cell(&mut grid, column, to_eighths(ideal), ideal_char);
+    }
+
+    let (axis, corner, rule) = if ascii {
+        ('|', '+', '-')
+    } else {
+        ('│', '└', '─')
+    };
+    let mut lines: Vec<String> = grid
+        .into_iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            let label = match row {
+                0 => max_total.to_string(),
+                row if row == height - 1 => "0".to_owned(),
+                _ => "".to_owned(),
+            };
+            let cells: String = cells.into_iter().collect();
+            format!("{:>label_width$}{}{}", label, axis, cells.trim_end())
+        })
+        .collect();
+    lines.push(format!(
+        "{}{}{}",
+        " ".repeat(label_width),
+        corner,
+        rule.to_string().repeat(columns.len())
+    ));
+
+    let first_date = points[0].date.to_string();
+    let last_date = points[points.len() - 1].date.to_string();
+    let gap = (columns.len() + 1).saturating_sub(first_date.len() + last_date.len());
+    lines.push(if points.len() > 1 && gap > 0 {
+        format!(
+            "{}{}{}{}",
+            " ".repeat(label_width),
+            first_date,
+            " ".repeat(gap),
+            last_date
+        )
+    } else {
+        format!("{}{}", " ".repeat(label_width), first_date)
+    });
+    lines.push(match kind {
+        ChartKind::Burndown => format!("{} remaining  {} ideal", bar_char, ideal_char),
+        ChartKind::Burnup => format!(
+            "{} completed  {} scope  {} ideal",
+            bar_char, scope_char, ideal_char
+        ),
+    });
+    lines
+}
+
+// the point shown in each column. With room to spare every day gets the same number of columns,
+// otherwise days are sampled evenly, always keeping the first and last
+fn get_columns(point_count: usize, width: usize) -> Vec<usize> {
+    if point_count == 0 {
+        return vec![];
+    }
+    if point_count <= width {
+        let columns_per_point = width / point_count;
+        return (0..point_count * columns_per_point)
+            .map(|column| column / columns_per_point)
+            .collect();
+    }
+    if width == 1 {
+        return vec![point_count - 1];
+    }
+    (0..width)
+        .map(|column| column * (point_count - 1) / (width - 1))
+        .collect()
+}
+
+// marks the row a line at height eighths passes through, nothing is drawn for zero
+fn set_line_cell(grid: &mut [Vec<char>], column: usize, eighths: usize, line_char: char) {
+    if eighths == 0 {
+        return;
+    }
+    let level = ((eighths - 1) / 8).min(grid.len() - 1);
+    let row = grid.len() - 1 - level;
+    grid[row][column] = line_char;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Sprint, Story};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    fn change(story_id: u32, day: u32, from: Option<Status>, to: Status) -> StatusChange {
+        StatusChange {
+            story_id,
+            date: date(day),
+            from,
+            to,
+        }
+    }
+
+    // an epic with a story from before the log, one created on the 2nd and closed on the 4th and
+    // one created on the 3rd
+    fn get_db_state() -> DBState {
+        let mut db_state = DBState::default();
+        let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        for status in [Status::Open, Status::Closed, Status::InProgress] {
+            let mut story = Story::new("".to_owned(), "".to_owned());
+            story.status = status;
+            db_state.add_story(story, epic_id).unwrap();
+        }
+        db_state.status_changes = vec![
+            change(3, 2, None, Status::Open),
+            change(4, 3, None, Status::Open),
+            change(3, 4, Some(Status::Open), Status::Closed),
+            change(4, 4, Some(Status::Open), Status::InProgress),
+        ];
+        db_state
+    }
+
+    fn get_work(points: &[ChartPoint]) -> Vec<Option<(usize, usize)>> {
+        points
+            .iter()
+            .map(|point| point.work.map(|work| (work.total, work.completed)))
+            .collect()
+    }
+
+    #[test]
+    fn get_series_should_count_stories_from_the_log() {
+        let db_state = get_db_state();
+
+        let points = get_series(&db_state, ChartScope::Epic(1), date(1), date(6), date(5)).unwrap();
+
+        assert_eq!(
+            get_work(&points),
+            vec![
+                Some((1, 0)),
+                Some((2, 0)),
+                Some((3, 0)),
+                Some((3, 1)),
+                Some((3, 1)),
+                None
+            ]
+        );
+        let ideal: Vec<f64> = points.iter().map(|point| point.ideal).collect();
+        assert_eq!(ideal, vec![1.0, 0.8, 0.6, 0.4, 0.2, 0.0]);
+
+        assert!(get_series(&db_state, ChartScope::Epic(9), date(1), date(6), date(5)).is_err());
+        assert!(get_series(&db_state, ChartScope::All, date(6), date(1), date(5)).is_err());
+    }
+
+    #[test]
+    fn get_default_range_should_depend_on_the_scope() {
+        let mut db_state = get_db_state();
+        db_state.sprints.insert(
+            9,
+            Sprint::new("".to_owned(), "".to_owned(), date(8), date(21)),
+        );
+
+        assert_eq!(
+            get_default_range(&db_state, ChartScope::Epic(1), date(10)).unwrap(),
+            (date(2), date(10))
+        );
+        assert_eq!(
+            get_default_range(&db_state, ChartScope::Sprint(9), date(10)).unwrap(),
+            (date(8), date(21))
+        );
+        assert_eq!(
+            get_default_range(&db_state, ChartScope::All, date(31)).unwrap(),
+            (date(2), date(31))
+        );
+    }
+
+    #[test]
+    fn write_series_csv_should_write_a_row_per_day() {
+        let points =
+            get_series(&get_db_state(), ChartScope::All, date(3), date(5), date(4)).unwrap();
+        let mut csv = Vec::new();
+
+        write_series_csv(&points, &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "date,total,completed,remaining,ideal\n\
+             2024-01-03,3,0,3,3.00\n\
+             2024-01-04,3,1,2,1.50\n\
+             2024-01-05,,,,0.00\n"
+        );
+    }
+
+    #[test]
+    fn draw_chart_should_scale_to_the_width() {
+        let points =
+            get_series(&get_db_state(), ChartScope::All, date(3), date(5), date(5)).unwrap();
+
+        assert_eq!(
+            draw_chart(&points, ChartKind::Burndown, 8, 3, true),
+            vec![
+                "3|..",
+                " |##..##",
+                "0|######",
+                " +------",
+                " 2024-01-03",
+                "# remaining  . ideal",
+            ]
+        );
+        assert_eq!(
+            draw_chart(&points, ChartKind::Burnup, 5, 3, false)[..4],
+            ["3│──·", " │ ·", "0│ ██", " └───"]
+        );
+        assert_eq!(get_columns(10, 4), vec![0, 3, 6, 9]);
+        assert_eq!(get_columns(3, 7), vec![0, 0, 1, 1, 2, 2]);
+    }
+}
@@ -0,0 +1,871 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use itertools::Itertools;
+
+use crate::charts::{
+    draw_chart, get_default_range, get_series, write_series_csv, ChartKind, ChartOptions,
+};
+use crate::csv_export::{export_csv, CsvExportOptions};
+use crate::csv_import::{import_csv, ColumnMapping};
+use crate::db::{Database, JiraDatabase};
+use crate::dir_db::DirectoryDatabase;
+use crate::due_date::{parse_due_date, today};
+use crate::git_links::{link_commits, read_commits, GitConfig};
+use crate::html_export::export_html;
+use crate::issue_import::{add_imported_items, parse_github_issues, parse_jira_issues};
+use crate::merge_driver::merge_files;
+use crate::models::ChartScope;
+use crate::report::get_markdown_report;
+use crate::rpc::run_rpc;
+use crate::server::{serve, DEFAULT_ADDRESS};
+use crate::stats::{get_stats, get_text_summary};
+use crate::time_tracking::{
+    get_text_timesheet, get_timesheet, parse_work_date, write_timesheet_csv,
+};
+use crate::ui::get_terminal_width;
+
+pub const USAGE: &str = "usage:
+    my-jira [--line]
+    my-jira --project <key> <command>
+    my-jira export csv [--epic <id>] [--status <status>] [--epics] [--output <file>]
+    my-jira export html <directory>
+    my-jira import csv <file> [--dry-run] [--map <field>=<column>]...
+    my-jira import github|jira <file> [--report <file>]
+    my-jira report [--epic <id>] [--output <file>]
+    my-jira chart [--epic <id> | --sprint <id>] [--from <date>] [--to <date>] [--burnup] [--csv] [--ascii] [--output <file>]
+    my-jira stats [--json]
+    my-jira timesheet [--user <name>] [--week <date>] [--csv] [--output <file>]
+    my-jira serve [--address <host:port>]
+    my-jira rpc
+    my-jira link-commits [--repo <directory>] [--resolve]
+    my-jira split-db <directory>
+    my-jira merge-driver <base> <ours> <theirs>";
+
+// rows of bars drawn by the chart command
+const CHART_HEIGHT: usize = 15;
+
+// What to run, parsed from the command line arguments
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    // the interactive pages, line_mode forces the line-based input even on a real terminal
+    Interactive {
+        line_mode: bool,
+    },
+    ExportCsv {
+        options: CsvExportOptions,
+        // stdout when not set
+        output: Option<String>,
+    },
+    // read-only static site
+    ExportHtml {
+        dir: String,
+    },
+    ImportCsv {
+        path: String,
+        mapping: ColumnMapping,
+        // validate every row and report what would be created without writing anything
+        dry_run: bool,
+    },
+    // issues exported from another tracker
+    ImportIssues {
+        source: IssueSource,
+        path: String,
+        // where to write the id mapping and skipped fields, stdout when not set
+        report: Option<String>,
+    },
+    // Markdown report of the whole database or one epic
+    Report {
+        epic_id: Option<u32>,
+        output: Option<String>,
+    },
+    // burndown or burnup chart, or its series as CSV
+    Chart {
+        options: ChartOptions,
+        output: Option<String>,
+    },
+    // counts, progress and throughput, as JSON for scripts when json is set
+    Stats {
+        json: bool,
+    },
+    // time logged by user and week, every user and week unless filtered
+    Timesheet {
+        user: Option<String>,
+        // any day in the week
+        week: Option<NaiveDate>,
+        csv: bool,
+        output: Option<String>,
+    },
+    // JSON API over HTTP
+    Serve {
+        address: String,
+    },
+    // JSON-RPC over stdin and stdout, one message per line
+    Rpc,
+    // attach git commits to the stories their messages mention
+    LinkCommits {
+        // the configured repository when not set
+        repository: Option<String>,
+        // move stories to RESOLVED for "fixes #id", also turned on by the config
+        resolve: bool,
+    },
+    // copy the database into a directory with a file per item
+    SplitDb {
+        dir: String,
+    },
+    // git merge driver for the files written by split-db
+    MergeDriver {
+        base: String,
+        ours: String,
+        theirs: String,
+    },
+    // another command scoped to the project with this key, see JiraDatabase::read_db()
+    InProject {
+        key: String,
+        command: Box<Command>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IssueSource {
+    GitHub,
+    Jira,
+}
+
+// args are the arguments after the program name
+pub fn parse_args(args: &[String]) -> Result<Command> {
+    if let [flag, key, rest @ ..] = args {
+        if flag == "--project" && !key.starts_with("--") {
+            return parse_in_project(key, rest);
+        }
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => Ok(Command::Interactive { line_mode: false }),
+        ["--line"] => Ok(Command::Interactive { line_mode: true }),
+        ["export", "csv", flags @ ..] => parse_export_csv(flags),
+        ["export", "html", dir] if !dir.starts_with("--") => Ok(Command::ExportHtml {
+            dir: dir.to_string(),
+        }),
+        ["import", "csv", path, flags @ ..] if !path.starts_with("--") => {
+            parse_import_csv(path, flags)
+        }
+        ["import", source @ ("github" | "jira"), path, flags @ ..] if !path.starts_with("--") => {
+            parse_import_issues(source, path, flags)
+        }
+        ["report", flags @ ..] => parse_report(flags),
+        ["chart", flags @ ..] => parse_chart(flags),
+        ["stats"] => Ok(Command::Stats { json: false }),
+        ["stats", "--json"] => Ok(Command::Stats { json: true }),
+        ["timesheet", flags @ ..] => parse_timesheet(flags),
+        ["serve"] => Ok(Command::Serve {
+            address: DEFAULT_ADDRESS.to_owned(),
+        }),
+        ["serve", "--address", address] => Ok(Command::Serve {
+            address: address.to_string(),
+        }),
+        ["rpc"] => Ok(Command::Rpc),
+        ["link-commits", flags @ ..] => parse_link_commits(flags),
+        ["split-db", dir] if !dir.starts_with("--") => Ok(Command::SplitDb {
+            dir: dir.to_string(),
+        }),
+        ["merge-driver", base, ours, theirs] => Ok(Command::MergeDriver {
+            base: base.to_string(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+        }),
+        _ => Err(anyhow!("unknown command: {}", args.join(" "))),
+    }
+}
+
+// the interactive pages have their own project picker, and split-db copies every project
+fn parse_in_project(key: &str, args: &[String]) -> Result<Command> {
+    match parse_args(args)? {
+        Command::Interactive { .. }
+        | Command::SplitDb { .. }
+        | Command::MergeDriver { .. }
+        | Command::InProject { .. } => {
+            Err(anyhow!("--project can't be used with: {}", args.join(" ")))
+        }
+        command => Ok(Command::InProject {
+            key: key.to_owned(),
+            command: Box::new(command),
+        }),
+    }
+}
+
+fn parse_export_csv(flags: &[&str]) -> Result<Command> {
+    let mut options = CsvExportOptions::default();
+    let mut output = None;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--epics" => options.include_epics = true,
+            "--epic" => options.epic_id = Some(parse_id(get_flag_value(flag, flags.next())?)?),
+            "--status" => options.status = Some(get_flag_value(flag, flags.next())?.parse()?),
+            "--output" => output = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::ExportCsv { options, output })
+}
+
+fn parse_import_csv(path: &str, flags: &[&str]) -> Result<Command> {
+    let mut mapping = ColumnMapping::default();
+    let mut dry_run = false;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--dry-run" => dry_run = true,
+            "--map" => mapping.set(get_flag_value(flag, flags.next())?)?,
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::ImportCsv {
+        path: path.to_owned(),
+        mapping,
+        dry_run,
+    })
+}
+
+fn parse_import_issues(source: &str, path: &str, flags: &[&str]) -> Result<Command> {
+    let source = match source {
+        "github" => IssueSource::GitHub,
+        _ => IssueSource::Jira,
+    };
+    let mut report = None;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--report" => report = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::ImportIssues {
+        source,
+        path: path.to_owned(),
+        report,
+    })
+}
+
+fn parse_report(flags: &[&str]) -> Result<Command> {
+    let mut epic_id = None;
+    let mut output = None;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--epic" => epic_id = Some(parse_id(get_flag_value(flag, flags.next())?)?),
+            "--output" => output = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::Report { epic_id, output })
+}
+
+fn parse_chart(flags: &[&str]) -> Result<Command> {
+    let mut options = ChartOptions::default();
+    let mut output = None;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--epic" => {
+                options.scope = ChartScope::Epic(parse_id(get_flag_value(flag, flags.next())?)?)
+            }
+            "--sprint" => {
+                options.scope = ChartScope::Sprint(parse_id(get_flag_value(flag, flags.next())?)?)
+            }
+            "--from" => {
+                options.start = Some(parse_due_date(
+                    get_flag_value(flag, flags.next())?,
+                    today(),
+                )?)
+            }
+            "--to" => {
+                options.end = Some(parse_due_date(
+                    get_flag_value(flag, flags.next())?,
+                    today(),
+                )?)
+            }
+            "--burnup" => options.kind = ChartKind::Burnup,
+            "--csv" => options.csv = true,
+            "--ascii" => options.ascii = true,
+            "--output" => output = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::Chart { options, output })
+}
+
+fn parse_timesheet(flags: &[&str]) -> Result<Command> {
+    let mut user = None;
+    let mut week = None;
+    let mut csv = false;
+    let mut output = None;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--user" => user = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            "--week" => {
+                week = Some(parse_work_date(
+                    get_flag_value(flag, flags.next())?,
+                    today(),
+                )?)
+            }
+            "--csv" => csv = true,
+            "--output" => output = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::Timesheet {
+        user,
+        week,
+        csv,
+        output,
+    })
+}
+
+fn parse_link_commits(flags: &[&str]) -> Result<Command> {
+    let mut repository = None;
+    let mut resolve = false;
+
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        match *flag {
+            "--repo" => repository = Some(get_flag_value(flag, flags.next())?.to_owned()),
+            "--resolve" => resolve = true,
+            _ => return Err(anyhow!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(Command::LinkCommits {
+        repository,
+        resolve,
+    })
+}
+
+fn parse_id(id: &str) -> Result<u32> {
+    id.parse().with_context(|| anyhow!("invalid id: {}", id))
+}
+
+fn get_flag_value<'a>(flag: &str, value: Option<&&'a str>) -> Result<&'a str> {
+    value
+        .copied()
+        .ok_or_else(|| anyhow!("{} needs a value", flag))
+}
+
+// Runs a command that doesn't need the interactive pages
+pub fn run_command(command: Command, db: &JiraDatabase) -> Result<()> {
+    match command {
+        Command::Interactive { .. } => Err(anyhow!("interactive mode is run by main()")),
+        Command::ExportCsv { options, output } => {
+            let db_state = db.read_db()?;
+            export_csv(&db_state, &options, open_output(output)?)
+        }
+        Command::ExportHtml { dir } => {
+            let files = export_html(&db.read_db()?, Path::new(&dir))?;
+            println!("wrote {} files to {}", files, dir);
+            Ok(())
+        }
+        Command::ImportCsv {
+            path,
+            mapping,
+            dry_run,
+        } => {
+            let file =
+                File::open(&path).with_context(|| anyhow!("failed to open file: {}", path))?;
+            let summary = if dry_run {
+                import_csv(&mut db.read_db()?, file, &mapping)?
+            } else {
+                db.update_batch(|db_state| import_csv(db_state, file, &mapping))?
+            };
+            println!(
+                "{} {} epic(s) and {} stories{}",
+                if dry_run { "would create" } else { "created" },
+                summary.epics_created,
+                summary.stories_created,
+                if dry_run { ", nothing was written" } else { "" }
+            );
+            Ok(())
+        }
+        Command::ImportIssues {
+            source,
+            path,
+            report,
+        } => {
+            let json = fs::read_to_string(&path)
+                .with_context(|| anyhow!("failed to read file: {}", path))?;
+            let items = match source {
+                IssueSource::GitHub => parse_github_issues(&json),
+                IssueSource::Jira => parse_jira_issues(&json),
+            }
+            .with_context(|| anyhow!("failed to parse file: {}", path))?;
+            let import_report = db.update_batch(|db_state| add_imported_items(db_state, items))?;
+            open_output(report)?.write_all(import_report.get_text()?.as_bytes())?;
+            Ok(())
+        }
+        Command::Report { epic_id, output } => {
+            let report = get_markdown_report(&db.read_db()?, epic_id)?;
+            open_output(output)?.write_all(report.as_bytes())?;
+            Ok(())
+        }
+        Command::Chart { options, output } => {
+            let db_state = db.read_db()?;
+            let today = today();
+            let (start, end) = get_default_range(&db_state, options.scope, today)?;
+            let points = get_series(
+                &db_state,
+                options.scope,
+                options.start.unwrap_or(start),
+                options.end.unwrap_or(end),
+                today,
+            )?;
+            let mut output = open_output(output)?;
+            if options.csv {
+                return write_series_csv(&points, output);
+            }
+            let lines = draw_chart(
+                &points,
+                options.kind,
+                get_terminal_width(),
+                CHART_HEIGHT,
+                options.ascii,
+            );
+            writeln!(output, "{}", lines.join("\n"))?;
+            Ok(())
+        }
+        Command::Stats { json } => {
+            let stats = get_stats(&db.read_db()?, today());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print!("{}", get_text_summary(&stats)?);
+            }
+            Ok(())
+        }
+        Command::Timesheet {
+            user,
+            week,
+            csv,
+            output,
+        } => {
+            let rows = get_timesheet(&db.read_db()?, user.as_deref(), week);
+            let mut output = open_output(output)?;
+            if csv {
+                return write_timesheet_csv(&rows, output);
+            }
+            output.write_all(get_text_timesheet(&rows)?.as_bytes())?;
+            Ok(())
+        }
+        Command::Serve { address } => serve(db, &address),
+        Command::Rpc => run_rpc(
+            db,
+            Some(PathBuf::from(crate::get_db_path())),
+            io::stdin().lock(),
+            Arc::new(Mutex::new(io::stdout())),
+        ),
+        Command::LinkCommits {
+            repository,
+            resolve,
+        } => {
+            let config = GitConfig::load(crate::GIT_CONFIG_PATH)?;
+            let repository = repository.unwrap_or(config.repository);
+            let commits = read_commits(Path::new(&repository))?;
+            // the links and status changes are written together, so if a hook stops a status change
+            // nothing is linked and the next scan tries again
+            let resolve = resolve || config.resolve;
+            let summary = db.update_batch(|db_state| link_commits(db_state, &commits, resolve))?;
+            println!(
+                "linked {} commit(s) from {}",
+                summary.commits_linked, repository
+            );
+            for story_id in summary.stories_resolved {
+                println!("resolved story {}", story_id);
+            }
+            if !summary.stories_to_resolve.is_empty() {
+                println!(
+                    "fixed but not resolved, use --resolve: {}",
+                    summary.stories_to_resolve.iter().join(", ")
+                );
+            }
+            Ok(())
+        }
+        Command::SplitDb { dir } => {
+            let path = Path::new(&dir);
+            if path
+                .read_dir()
+                .is_ok_and(|mut entries| entries.next().is_some())
+            {
+                return Err(anyhow!("directory is not empty: {}", dir));
+            }
+            let db_state = db.read_db()?;
+            DirectoryDatabase {
+                dir: path.to_path_buf(),
+            }
+            .write_db(&db_state)?;
+            println!(
+                "wrote {} epic(s) and {} stories to {}",
+                db_state.epics.len(),
+                db_state.stories.len(),
+                dir
+            );
+            Ok(())
+        }
+        Command::MergeDriver { base, ours, theirs } => {
+            merge_files(Path::new(&base), Path::new(&ours), Path::new(&theirs))
+        }
+        Command::InProject { key, command } => {
+            let project_id = db
+                .read_db()?
+                .get_project_by_key(&key)
+                .ok_or_else(|| anyhow!("unknown project: {}", key))?;
+            db.set_current_project(Some(project_id));
+            run_command(*command, db)
+        }
+    }
+}
+
+// the file to write to, or stdout when there isn't one
+fn open_output(output: Option<String>) -> Result<Box<dyn Write>> {
+    match output {
+        Some(path) => {
+            let file =
+                File::create(&path).with_context(|| anyhow!("failed to create file: {}", path))?;
+            Ok(Box::new(file))
+        }
+        None => Ok(Box::new(io::stdout().lock())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Status;
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn parse_args_should_default_to_interactive() {
+        assert_eq!(
+            parse(&[]).unwrap(),
+            Command::Interactive { line_mode: false }
+        );
+        assert_eq!(
+            parse(&["--line"]).unwrap(),
+            Command::Interactive { line_mode: true }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_export_csv() {
+        assert_eq!(
+            parse(&["export", "csv"]).unwrap(),
+            Command::ExportCsv {
+                options: CsvExportOptions::default(),
+                output: None
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "export",
+                "csv",
+                "--epic",
+                "3",
+                "--status",
+                "in progress",
+                "--epics",
+                "--output",
+                "out.csv"
+            ])
+            .unwrap(),
+            Command::ExportCsv {
+                options: CsvExportOptions {
+                    epic_id: Some(3),
+                    status: Some(Status::InProgress),
+                    include_epics: true,
+                },
+                output: Some("out.csv".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_export_html() {
+        assert_eq!(
+            parse(&["export", "html", "site"]).unwrap(),
+            Command::ExportHtml {
+                dir: "site".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_import_csv() {
+        let mut mapping = ColumnMapping::default();
+        mapping.set("name=Summary").unwrap();
+        assert_eq!(
+            parse(&[
+                "import",
+                "csv",
+                "backlog.csv",
+                "--map",
+                "name=Summary",
+                "--dry-run"
+            ])
+            .unwrap(),
+            Command::ImportCsv {
+                path: "backlog.csv".to_owned(),
+                mapping,
+                dry_run: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_import_issues() {
+        assert_eq!(
+            parse(&["import", "github", "issues.json"]).unwrap(),
+            Command::ImportIssues {
+                source: IssueSource::GitHub,
+                path: "issues.json".to_owned(),
+                report: None
+            }
+        );
+        assert_eq!(
+            parse(&["import", "jira", "export.json", "--report", "ids.txt"]).unwrap(),
+            Command::ImportIssues {
+                source: IssueSource::Jira,
+                path: "export.json".to_owned(),
+                report: Some("ids.txt".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_report() {
+        assert_eq!(
+            parse(&["report"]).unwrap(),
+            Command::Report {
+                epic_id: None,
+                output: None
+            }
+        );
+        assert_eq!(
+            parse(&["report", "--output", "report.md", "--epic", "2"]).unwrap(),
+            Command::Report {
+                epic_id: Some(2),
+                output: Some("report.md".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_chart() {
+        assert_eq!(
+            parse(&["chart"]).unwrap(),
+            Command::Chart {
+                options: ChartOptions::default(),
+                output: None
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "chart",
+                "--sprint",
+                "4",
+                "--from",
+                "2024-01-01",
+                "--to",
+                "2024-01-14",
+                "--burnup",
+                "--csv",
+                "--ascii",
+                "--output",
+                "sprint.csv"
+            ])
+            .unwrap(),
+            Command::Chart {
+                options: ChartOptions {
+                    scope: ChartScope::Sprint(4),
+                    kind: ChartKind::Burnup,
+                    start: chrono::NaiveDate::from_ymd_opt(2024, 1, 1),
+                    end: chrono::NaiveDate::from_ymd_opt(2024, 1, 14),
+                    csv: true,
+                    ascii: true,
+                },
+                output: Some("sprint.csv".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_stats() {
+        assert_eq!(parse(&["stats"]).unwrap(), Command::Stats { json: false });
+        assert_eq!(
+            parse(&["stats", "--json"]).unwrap(),
+            Command::Stats { json: true }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_timesheet() {
+        assert_eq!(
+            parse(&["timesheet"]).unwrap(),
+            Command::Timesheet {
+                user: None,
+                week: None,
+                csv: false,
+                output: None,
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "timesheet",
+                "--user",
+                "ann",
+                "--week",
+                "2024-02-21",
+                "--csv",
+                "--output",
+                "hours.csv"
+            ])
+            .unwrap(),
+            Command::Timesheet {
+                user: Some("ann".to_owned()),
+                week: NaiveDate::from_ymd_opt(2024, 2, 21),
+                csv: true,
+                output: Some("hours.csv".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_serve() {
+        assert_eq!(
+            parse(&["serve"]).unwrap(),
+            Command::Serve {
+                address: "127.0.0.1:8080".to_owned()
+            }
+        );
+        assert_eq!(
+            parse(&["serve", "--address", "0.0.0.0:3000"]).unwrap(),
+            Command::Serve {
+                address: "0.0.0.0:3000".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_rpc() {
+        assert_eq!(parse(&["rpc"]).unwrap(), Command::Rpc);
+    }
+
+    #[test]
+    fn parse_args_should_parse_link_commits() {
+        assert_eq!(
+            parse(&["link-commits"]).unwrap(),
+            Command::LinkCommits {
+                repository: None,
+                resolve: false
+            }
+        );
+        assert_eq!(
+            parse(&["link-commits", "--resolve", "--repo", "../app"]).unwrap(),
+            Command::LinkCommits {
+                repository: Some("../app".to_owned()),
+                resolve: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_merge_driver() {
+        assert_eq!(
+            parse(&["merge-driver", "a", "b", "c"]).unwrap(),
+            Command::MergeDriver {
+                base: "a".to_owned(),
+                ours: "b".to_owned(),
+                theirs: "c".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_project() {
+        assert_eq!(
+            parse(&["--project", "web", "serve"]).unwrap(),
+            Command::InProject {
+                key: "web".to_owned(),
+                command: Box::new(Command::Serve {
+                    address: DEFAULT_ADDRESS.to_owned()
+                })
+            }
+        );
+        assert_eq!(
+            parse(&["--project", "WEB", "rpc"]).unwrap(),
+            Command::InProject {
+                key: "WEB".to_owned(),
+                command: Box::new(Command::Rpc)
+            }
+        );
+        assert_eq!(
+            parse(&["--project", "WEB", "stats", "--json"]).unwrap(),
+            Command::InProject {
+                key: "WEB".to_owned(),
+                command: Box::new(Command::Stats { json: true })
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_fail_for_invalid_args() {
+        assert!(parse(&["--project"]).is_err());
+        assert!(parse(&["--project", "WEB"]).is_err());
+        assert!(parse(&["--project", "--line"]).is_err());
+        assert!(parse(&["--project", "WEB", "split-db", "db"]).is_err());
+        assert!(parse(&["--project", "WEB", "--project", "APP", "rpc"]).is_err());
+        assert!(parse(&["serve", "--project", "WEB"]).is_err());
+        assert!(parse(&["export"]).is_err());
+        assert!(parse(&["export", "csv", "--epic"]).is_err());
+        assert!(parse(&["export", "csv", "--epic", "one"]).is_err());
+        assert!(parse(&["export", "csv", "--status", "done"]).is_err());
+        assert!(parse(&["export", "csv", "--colour"]).is_err());
+        assert!(parse(&["export", "html"]).is_err());
+        assert!(parse(&["serve", "--address"]).is_err());
+        assert!(parse(&["rpc", "--address"]).is_err());
+        assert!(parse(&["link-commits", "--repo"]).is_err());
+        assert!(parse(&["split-db"]).is_err());
+        assert!(parse(&["merge-driver", "a", "b"]).is_err());
+        assert!(parse(&["--line", "export"]).is_err());
+        assert!(parse(&["import", "csv"]).is_err());
+        assert!(parse(&["report", "--epic", "-1"]).is_err());
+        assert!(parse(&["chart", "--from", "soon"]).is_err());
+        assert!(parse(&["chart", "--sprint"]).is_err());
+        assert!(parse(&["stats", "--csv"]).is_err());
+        assert!(parse(&["timesheet", "--user"]).is_err());
+        assert!(parse(&["timesheet", "--week", "someday"]).is_err());
+        assert!(parse(&["import", "gitlab", "issues.json"]).is_err());
+        assert!(parse(&["import", "jira", "--report", "ids.txt"]).is_err());
+        assert!(parse(&["import", "csv", "--dry-run"]).is_err());
+        assert!(parse(&["import", "csv", "a.csv", "--map", "owner=Assignee"]).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::models::{DBState, Status};
+
+pub const CSV_HEADER: [&str; 7] = [
+    "type",
+    "epic_id",
+    "epic_name",
+    "story_id",
+    "name",
+    "description",
+    "status",
+];
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct CsvExportOptions {
+    // only export this epic and its stories
+    pub epic_id: Option<u32>,
+    // only export stories with this status
+    pub status: Option<Status>,
+    // write a row for each exported epic ahead of its stories
+    pub include_epics: bool,
+}
+
+// Writes one row per story, in id order, with the id and name of its epic. Quoting of commas,
+// quotes and newlines is left to the csv writer
+pub fn export_csv(
+    db_state: &DBState,
+    options: &CsvExportOptions,
+    writer: impl Write,
+) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(CSV_HEADER)?;
+
+    let mut epics: Vec<_> = db_state
+        .epics
+        .iter()
+        .filter(|(id, _)| options.epic_id.is_none_or(|epic_id| **id == epic_id))
+        .collect();
+    if let Some(epic_id) = options.epic_id {
+        if epics.is_empty() {
+            return Err(anyhow!("could not find epic with id: {}", epic_id));
+        }
+    }
+    epics.sort_by_key(|(id, _)| **id);
+
+    for (epic_id, epic) in epics {
+        let epic_id = epic_id.to_string();
+        if options.include_epics {
+            writer.write_record([
+                "epic",
+                &epic_id,
+                &epic.name,
+                "",
+                &epic.name,
+                &epic.description,
+                &epic.status.to_string(),
+            ])?;
+        }
+
+        let mut story_ids = epic.stories.clone();
+        story_ids.sort();
+        for story_id in story_ids {
+            let story = db_state
+                .stories
+                .get(&story_id)
+                .ok_or_else(|| anyhow!("could not find story with id: {}", story_id))?;
+            if options
+                .status
+                .as_ref()
+                .is_some_and(|status| *status != story.status)
+            {
+                continue;
+            }
+
+            writer.write_record([
+                "story",
+                &epic_id,
+                &epic.name,
+                &story_id.to_string(),
+                &story.name,
+                &story.description,
+                &story.status.to_string(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::models::{Epic, Story};
+
+    fn get_db_state() -> DBState {
+        let mut epic = Epic::new("Fruit, \"fresh\"".to_owned(), "Buy\nfruit".to_owned());
+        epic.stories = vec![3, 2];
+        let mut resolved = Story::new("Apple".to_owned(), "".to_owned());
+        resolved.status = Status::Resolved;
+
+        DBState {
+            last_item_id: 4,
+            epics: HashMap::from([(1, epic), (4, Epic::new("Veg".to_owned(), "".to_owned()))]),
+            stories: HashMap::from([
+                (
+                    2,
+                    Story::new("Banana".to_owned(), "Eat, then peel".to_owned()),
+                ),
+                (3, resolved),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn export(options: &CsvExportOptions) -> Result<String> {
+        let mut output = Vec::new();
+        export_csv(&get_db_state(), options, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn export_csv_should_write_a_row_per_story() {
+        assert_eq!(
+            export(&CsvExportOptions::default()).unwrap(),
+            "type,epic_id,epic_name,story_id,name,description,status\n\
+             story,1,\"Fruit, \"\"fresh\"\"\",2,Banana,\"Eat, then peel\",OPEN\n\
+             story,1,\"Fruit, \"\"fresh\"\"\",3,Apple,,RESOLVED\n"
+        );
+    }
+
+    #[test]
+    fn export_csv_should_include_epics() {
+        let options = CsvExportOptions {
+            include_epics: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            export(&options).unwrap(),
+            "type,epic_id,epic_name,story_id,name,description,status\n\
+             epic,1,\"Fruit, \"\"fresh\"\"\",,\"Fruit, \"\"fresh\"\"\",\"Buy\nfruit\",OPEN\n\
+             story,1,\"Fruit, \"\"fresh\"\"\",2,Banana,\"Eat, then peel\",OPEN\n\
+             story,1,\"Fruit, \"\"fresh\"\"\",3,Apple,,RESOLVED\n\
+             epic,4,Veg,,Veg,,OPEN\n"
+        );
+    }
+
+    #[test]
+    fn export_csv_should_filter_by_epic_and_status() {
+        let options = CsvExportOptions {
+            epic_id: Some(4),
+            include_epics: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            export(&options).unwrap(),
+            "type,epic_id,epic_name,story_id,name,description,status\nepic,4,Veg,,Veg,,OPEN\n"
+        );
+
+        let options = CsvExportOptions {
+            status: Some(Status::Resolved),
+            ..Default::default()
+        };
+        let output = export(&options).unwrap();
+        assert!(output.contains("Apple"));
+        assert!(!output.contains("Banana"));
+
+        let options = CsvExportOptions {
+            epic_id: Some(999),
+            ..Default::default()
+        };
+        assert!(export(&options).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use crate::models::{DBState, Epic, Status, Story};
+
+// Which csv column holds each field. The defaults match the columns written by export csv
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ColumnMapping {
+    // "epic" or "story", every row is a story when the column is missing
+    pub item_type: String,
+    pub name: String,
+    pub description: String,
+    pub status: String,
+    // name of the story's epic
+    pub epic: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            item_type: "type".to_owned(),
+            name: "name".to_owned(),
+            description: "description".to_owned(),
+            status: "status".to_owned(),
+            epic: "epic_name".to_owned(),
+        }
+    }
+}
+
+impl ColumnMapping {
+    // sets the column for one field from a "field=column" argument e.g. "name=Summary"
+    pub fn set(&mut self, mapping: &str) -> Result<()> {
+        let (field, column) = mapping
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected field=column but found '{}'", mapping))?;
+        let column = column.trim().to_owned();
+        match field.trim() {
+            "type" => self.item_type = column,
+            "name" => self.name = column,
+            "description" => self.description = column,
+            "status" => self.status = column,
+            "epic" => self.epic = column,
+            field => return Err(anyhow!("unknown field '{}'", field)),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ImportSummary {
+    pub epics_created: usize,
+    pub stories_created: usize,
+}
+
+enum RowItem {
+    Epic(Epic),
+    Story { story: Story, epic_name: String },
+}
+
+// Adds every row of the csv to db_state. Rows are validated before anything is added, if any row
+// is invalid db_state is left untouched and the error lists the problem with every row.
+// Stories are added to the epic with a matching name, either from an epic row in the same file or
+// already in the database. Epics that don't exist yet are created with just a name
+pub fn import_csv(
+    db_state: &mut DBState,
+    reader: impl Read,
+    mapping: &ColumnMapping,
+) -> Result<ImportSummary> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let get_column = |column: &str| headers.iter().position(|header| header.trim() == column);
+    let columns = [
+        get_column(&mapping.item_type),
+        get_column(&mapping.name),
+        get_column(&mapping.description),
+        get_column(&mapping.status),
+        get_column(&mapping.epic),
+    ];
+    if columns[1].is_none() {
+        return Err(anyhow!("the csv has no '{}' column", mapping.name));
+    }
+
+    // (line, problem) for every invalid row
+    let mut errors: Vec<(u64, String)> = Vec::new();
+    let mut items = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let [item_type, name, description, status, epic_name] =
+            columns.map(|column| column.and_then(|i| record.get(i)).unwrap_or("").trim());
+
+        match parse_row(item_type, name, description, status, epic_name) {
+            Ok(item) => items.push((line, item)),
+            Err(e) => errors.push((line, e.to_string())),
+        }
+    }
+
+    let mut epic_ids: HashMap<String, u32> = HashMap::new();
+    let mut new_epic_names = HashSet::new();
+    for (line, item) in &items {
+        match item {
+            RowItem::Epic(epic) => {
+                if db_state
+                    .epics
+                    .values()
+                    .any(|existing| existing.name == epic.name)
+                    || !new_epic_names.insert(&epic.name)
+                {
+                    errors.push((*line, format!("epic '{}' already exists", epic.name)));
+                }
+            }
+            RowItem::Story { epic_name, .. } => {
+                let matches = db_state
+                    .epics
+                    .iter()
+                    .filter(|(_, epic)| epic.name == *epic_name)
+                    .map(|(id, _)| *id)
+                    .collect_vec();
+                match matches.as_slice() {
+                    [] => {}
+                    [epic_id] => {
+                        epic_ids.insert(epic_name.clone(), *epic_id);
+                    }
+                    _ => errors.push((
+                        *line,
+                        format!("more than one epic is named '{}'", epic_name),
+                    )),
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|(line, _)| *line);
+        return Err(anyhow!(
+            "{} problem(s) found, nothing was imported:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .map(|(line, error)| format!("line {}: {}", line, error))
+                .join("\n")
+        ));
+    }
+
+    // epics first so stories can be added to epics defined further down the file
+    let mut summary = ImportSummary::default();
+    let (epics, stories): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .partition(|(_, item)| matches!(item, RowItem::Epic(_)));
+    for (_, item) in epics {
+        if let RowItem::Epic(epic) = item {
+            let name = epic.name.clone();
+            epic_ids.insert(name, db_state.add_epic(epic));
+            summary.epics_created += 1;
+        }
+    }
+    for (_, item) in stories {
+        if let RowItem::Story { story, epic_name } = item {
+            let epic_id = match epic_ids.get(&epic_name) {
+                Some(epic_id) => *epic_id,
+                None => {
+                    summary.epics_created += 1;
+                    let epic_id = db_state.add_epic(Epic::new(epic_name.clone(), "".to_owned()));
+                    epic_ids.insert(epic_name, epic_id);
+                    epic_id
+                }
+            };
+            db_state.add_story(story, epic_id)?;
+            summary.stories_created += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+fn parse_row(
+    item_type: &str,
+    name: &str,
+    description: &str,
+    status: &str,
+    epic_name: &str,
+) -> Result<RowItem> {
+    if name.is_empty() {
+        return Err(anyhow!("name must not be empty"));
+    }
+    let status = if status.is_empty() {
+        Status::Open
+    } else {
+        status.parse()?
+    };
+
+    match item_type.to_lowercase().as_str() {
+        "epic" => {
+            let mut epic = Epic::new(name.to_owned(), description.to_owned());
+            epic.status = status;
+            Ok(RowItem::Epic(epic))
+        }
+        "story" | "" => {
+            if epic_name.is_empty() {
+                return Err(anyhow!("story '{}' has no epic", name));
+            }
+            let mut story = Story::new(name.to_owned(), description.to_owned());
+            story.status = status;
+            Ok(RowItem::Story {
+                story,
+                epic_name: epic_name.to_owned(),
+            })
+        }
+        item_type => Err(anyhow!("unknown type '{}'", item_type)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_export::{export_csv, CsvExportOptions};
+
+    fn get_db_state() -> DBState {
+        DBState {
+            last_item_id: 1,
+            epics: HashMap::from([(1, Epic::new("Fruit".to_owned(), "".to_owned()))]),
+            stories: HashMap::new(),
+            ..Default::default()
+        }
+    }
+
+    fn import(db_state: &mut DBState, csv: &str) -> Result<ImportSummary> {
+        import_csv(db_state, csv.as_bytes(), &ColumnMapping::default())
+    }
+
+    #[test]
+    fn import_csv_should_create_epics_and_stories() {
+        let mut db_state = get_db_state();
+        let csv = "name,description,status,epic_name\n\
+                   Apple,\"Crunchy, \"\"red\"\"\",resolved,Fruit\n\
+                   Carrot,,,Veg\n\
+                   Pea,,in progress,Veg\n";
+
+        let summary = import(&mut db_state, csv).unwrap();
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                epics_created: 1,
+                stories_created: 3
+            }
+        );
+        assert_eq!(db_state.last_item_id, 5);
+        assert_eq!(db_state.epics[&1].stories, vec![2]);
+        assert_eq!(db_state.stories[&2].description, "Crunchy, \"red\"");
+        assert_eq!(db_state.stories[&2].status, Status::Resolved);
+        assert_eq!(db_state.epics[&3].name, "Veg");
+        assert_eq!(db_state.epics[&3].stories, vec![4, 5]);
+        assert_eq!(db_state.stories[&4].status, Status::Open);
+        let created: Vec<(u32, Option<Status>, Status)> = db_state
+            .status_changes
+            .iter()
+            .map(|change| (change.story_id, change.from.clone(), change.to.clone()))
+            .collect();
+        assert_eq!(
+            created,
+            vec![
+                (2, None, Status::Resolved),
+                (4, None, Status::Open),
+                (5, None, Status::InProgress)
+            ]
+        );
+    }
+
+    #[test]
+    fn import_csv_should_read_exported_csv() {
+        let mut exported = get_db_state();
+        exported.epics.get_mut(&1).unwrap().name = "Veg".to_owned();
+        exported.epics.get_mut(&1).unwrap().description = "Greens,\nand roots".to_owned();
+        exported
+            .add_story(Story::new("Kale".to_owned(), "".to_owned()), 1)
+            .unwrap();
+        let options = CsvExportOptions {
+            include_epics: true,
+            ..Default::default()
+        };
+        let mut csv = Vec::new();
+        export_csv(&exported, &options, &mut csv).unwrap();
+
+        let mut db_state = get_db_state();
+        let summary = import(&mut db_state, &String::from_utf8(csv).unwrap()).unwrap();
+
+        assert_eq!(summary.epics_created, 1);
+        assert_eq!(db_state.epics[&2].description, "Greens,\nand roots");
+        assert_eq!(db_state.epics[&2].stories, vec![3]);
+        assert_eq!(db_state.stories[&3].name, "Kale");
+    }
+
+    #[test]
+    fn import_csv_should_use_column_mapping() {
+        let mut mapping = ColumnMapping::default();
+        mapping.set("name=Summary").unwrap();
+        mapping.set("epic = Parent").unwrap();
+        assert!(mapping.set("owner=Assignee").is_err());
+        assert!(mapping.set("name").is_err());
+
+        let mut db_state = get_db_state();
+        let csv = "Summary,Parent\nApple,Fruit\n";
+        import_csv(&mut db_state, csv.as_bytes(), &mapping).unwrap();
+
+        assert_eq!(db_state.epics[&1].stories, vec![2]);
+    }
+
+    #[test]
+    fn import_csv_should_report_every_invalid_row_and_change_nothing() {
+        let mut db_state = get_db_state();
+        db_state
+            .epics
+            .insert(9, Epic::new("Twin".to_owned(), "".to_owned()));
+        db_state
+            .epics
+            .insert(10, Epic::new("Twin".to_owned(), "".to_owned()));
+        let csv = "type,name,status,epic_name\n\
+                   story,Apple,,Fruit\n\
+                   story,,,Fruit\n\
+                   story,Banana,done,Fruit\n\
+                   story,Cherry,,\n\
+                   epic,Fruit,,\n\
+                   task,Date,,Fruit\n\
+                   story,Elder,,Twin\n";
+
+        let error = import(&mut db_state, csv).unwrap_err().to_string();
+
+        assert_eq!(
+            error,
+            "6 problem(s) found, nothing was imported:\n\
+             line 3: name must not be empty\n\
+             line 4: invalid status: done\n\
+             line 5: story 'Cherry' has no epic\n\
+             line 6: epic 'Fruit' already exists\n\
+             line 7: unknown type 'task'\n\
+             line 8: more than one epic is named 'Twin'"
+        );
+        assert_eq!(db_state.last_item_id, 1);
+        assert!(db_state.stories.is_empty());
+
+        assert!(import(&mut db_state, "description\nApple\n").is_err());
+    }
+}
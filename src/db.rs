@@ -1,69 +1,256 @@
+use std::cell::Cell;
 use std::fs;
+use std::path::PathBuf;
 
 use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
 
-use crate::models::{DBState, Epic, Status, Story};
+use crate::dir_db::DirectoryDatabase;
+use crate::due_date::today;
+use crate::hooks::{HookedDatabase, Hooks};
+use crate::models::{
+    DBState, Epic, LinkKind, ListPage, NotFoundError, Project, SortOrder, Sprint, Status, Story,
+    Worklog,
+};
 
 pub struct JiraDatabase {
-    pub database: Box<dyn Database>,
+    database: Box<dyn Database>,
+    // the project the pages are scoped to, None for every epic. See read_db()
+    current_project: Cell<Option<u32>>,
 }
 
 impl JiraDatabase {
     pub fn new(file_path: String) -> Self {
+        Self::from_database(Box::new(JSONFileDatabase { file_path }))
+    }
+
+    // one file per item in dir, see DirectoryDatabase
+    pub fn new_directory(dir: PathBuf) -> Self {
+        Self::from_database(Box::new(DirectoryDatabase { dir }))
+    }
+
+    pub fn from_database(database: Box<dyn Database>) -> Self {
+        Self {
+            database,
+            current_project: Cell::new(None),
+        }
+    }
+
+    // runs the hooks around every write, see HookedDatabase
+    pub fn with_hooks(self, hooks: Hooks) -> Self {
+        if hooks.is_empty() {
+            return self;
+        }
         Self {
-            database: Box::new(JSONFileDatabase { file_path }),
+            database: Box::new(HookedDatabase::new(self.database, hooks)),
+            current_project: self.current_project,
         }
     }
 
+    // only the epics and stories of the current project when there is one, see
+    // DBState::get_project_view()
     pub fn read_db(&self) -> Result<DBState> {
-        self.database.read_db()
+        let db_state = self.database.read_db()?;
+        match self.current_project.get() {
+            Some(project_id) => db_state.get_project_view(project_id),
+            None => Ok(db_state),
+        }
+    }
+
+    pub fn get_current_project(&self) -> Option<u32> {
+        self.current_project.get()
+    }
+
+    pub fn set_current_project(&self, project_id: Option<u32>) {
+        self.current_project.set(project_id);
+    }
+
+    pub fn create_project(&self, project: Project) -> Result<u32> {
+        self.update_batch(|db_state| db_state.add_project(project))
     }
 
+    // moves the epic and its stories into another project
+    pub fn move_epic(&self, epic_id: u32, project_id: u32) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_epic_in_project(db_state, epic_id)?;
+            db_state.move_epic(epic_id, project_id)
+        })
+    }
+
+    // With a current project, items outside it can't be changed, as if they weren't in the
+    // database. See read_db()
+    fn check_epic_in_project(&self, db_state: &DBState, epic_id: u32) -> Result<()> {
+        if let Some(project_id) = self.current_project.get() {
+            if db_state.get_project_id(epic_id) != Some(project_id) {
+                return Err(anyhow!(NotFoundError("could not find epic in database!")));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_story_in_project(&self, db_state: &DBState, story_id: u32) -> Result<()> {
+        if let Some(project_id) = self.current_project.get() {
+            let epic_id = db_state.get_epic_id(story_id);
+            if epic_id.and_then(|id| db_state.get_project_id(id)) != Some(project_id) {
+                return Err(anyhow!(NotFoundError("could not find story in database!")));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_sprint_in_project(&self, db_state: &DBState, sprint_id: u32) -> Result<()> {
+        if let Some(project_id) = self.current_project.get() {
+            let sprint = db_state.sprints.get(&sprint_id);
+            if sprint.map(|sprint| sprint.project_id) != Some(Some(project_id)) {
+                return Err(anyhow!(NotFoundError("could not find sprint in database!")));
+            }
+        }
+        Ok(())
+    }
+
+    // messages from hooks run since the last call, to be shown to the user
+    pub fn take_hook_output(&self) -> Vec<String> {
+        self.database.take_hook_output()
+    }
+
+    // new epics go into the current project
     pub fn create_epic(&self, epic: Epic) -> Result<u32> {
         let mut parsed = self.database.read_db()?;
 
-        let last_id = parsed.last_item_id;
-        let new_id = last_id + 1;
-
-        parsed.last_item_id = new_id;
-        parsed.epics.insert(new_id, epic);
+        let new_id = parsed.add_epic(epic);
+        if let Some(project_id) = self.current_project.get() {
+            parsed.move_epic(new_id, project_id)?;
+        }
 
         self.database.write_db(&parsed)?;
         Ok(new_id)
     }
 
+    // the epic must be in the current project
     pub fn create_story(&self, story: Story, epic_id: u32) -> Result<u32> {
         let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
 
-        let last_id = parsed.last_item_id;
-        let new_id = last_id + 1;
-
-        parsed.last_item_id = new_id;
-        parsed.stories.insert(new_id, story);
-        parsed
-            .epics
-            .get_mut(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database!"))?
-            .stories
-            .push(new_id);
+        let new_id = parsed.add_story(story, epic_id)?;
 
         self.database.write_db(&parsed)?;
         Ok(new_id)
     }
 
+    // Applies every change in f to the database in a single write. Nothing is written if f fails,
+    // so a batch of changes either all succeed or none do. With a current project the batch fails
+    // if it changes anything outside the project, and new epics and sprints go into the project
+    pub fn update_batch<T>(&self, f: impl FnOnce(&mut DBState) -> Result<T>) -> Result<T> {
+        let mut parsed = self.database.read_db()?;
+        let before = self
+            .current_project
+            .get()
+            .map(|project_id| (project_id, parsed.clone()));
+
+        let result = f(&mut parsed)?;
+        if let Some((project_id, before)) = before {
+            self.check_batch_in_project(project_id, &before, &mut parsed)?;
+        }
+
+        self.database.write_db(&parsed)?;
+        Ok(result)
+    }
+
+    fn check_batch_in_project(
+        &self,
+        project_id: u32,
+        before: &DBState,
+        after: &mut DBState,
+    ) -> Result<()> {
+        for (epic_id, epic) in &before.epics {
+            if after.epics.get(epic_id) != Some(epic) {
+                self.check_epic_in_project(before, *epic_id)?;
+            }
+        }
+        for (story_id, story) in &before.stories {
+            if after.stories.get(story_id) != Some(story) {
+                self.check_story_in_project(before, *story_id)?;
+            }
+        }
+        for (sprint_id, sprint) in &before.sprints {
+            if after.sprints.get(sprint_id) != Some(sprint) {
+                self.check_sprint_in_project(before, *sprint_id)?;
+            }
+        }
+
+        // the history, time and links of stories outside the project must be left as they were
+        let mut story_ids = before.get_project_story_ids(project_id)?;
+        story_ids.extend(after.get_project_story_ids(project_id)?);
+        let is_outside = |story_id: &u32| !story_ids.contains(story_id);
+        let unchanged = before
+            .status_changes
+            .iter()
+            .filter(|change| is_outside(&change.story_id))
+            .eq(after
+                .status_changes
+                .iter()
+                .filter(|change| is_outside(&change.story_id)))
+            && before
+                .worklogs
+                .iter()
+                .filter(|worklog| is_outside(&worklog.story_id))
+                .eq(after
+                    .worklogs
+                    .iter()
+                    .filter(|worklog| is_outside(&worklog.story_id)))
+            && before
+                .timers
+                .iter()
+                .filter(|timer| is_outside(&timer.story_id))
+                .eq(after
+                    .timers
+                    .iter()
+                    .filter(|timer| is_outside(&timer.story_id)))
+            && before
+                .story_links
+                .iter()
+                .filter(|link| is_outside(&link.from) || is_outside(&link.to))
+                .eq(after
+                    .story_links
+                    .iter()
+                    .filter(|link| is_outside(&link.from) || is_outside(&link.to)));
+        if !unchanged {
+            return Err(anyhow!(NotFoundError("could not find story in database!")));
+        }
+
+        let new_epic_ids: Vec<u32> = after
+            .epics
+            .keys()
+            .filter(|id| !before.epics.contains_key(id) && after.get_project_id(**id).is_none())
+            .copied()
+            .collect();
+        for epic_id in new_epic_ids {
+            after.move_epic(epic_id, project_id)?;
+        }
+        for (sprint_id, sprint) in after.sprints.iter_mut() {
+            if !before.sprints.contains_key(sprint_id) {
+                sprint.project_id = Some(project_id);
+            }
+        }
+        Ok(())
+    }
+
     pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
         let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
 
-        for story_id in &parsed
+        let story_ids = parsed
             .epics
             .get(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database!"))?
+            .ok_or_else(|| anyhow!(NotFoundError("could not find epic in database!")))?
             .stories
-        {
-            parsed.stories.remove(story_id);
+            .clone();
+        for story_id in story_ids {
+            parsed.remove_story(story_id)?;
         }
 
         parsed.epics.remove(&epic_id);
+        parsed.remove_epic_references(epic_id);
 
         self.database.write_db(&parsed)?;
         Ok(())
@@ -71,20 +258,21 @@ impl JiraDatabase {
 
     pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
         let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
 
         let epic = parsed
             .epics
             .get_mut(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+            .ok_or_else(|| anyhow!(NotFoundError("could not find epic in database!")))?;
 
         let story_index = epic
             .stories
             .iter()
             .position(|id| id == &story_id)
-            .ok_or_else(|| anyhow!("story id not found in epic stories vector"))?;
+            .ok_or_else(|| anyhow!(NotFoundError("story id not found in epic stories vector")))?;
         epic.stories.remove(story_index);
 
-        parsed.stories.remove(&story_id);
+        parsed.remove_story(story_id)?;
 
         self.database.write_db(&parsed)?;
         Ok(())
@@ -92,25 +280,191 @@ impl JiraDatabase {
 
     pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
         let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
 
         parsed
             .epics
             .get_mut(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database!"))?
+            .ok_or_else(|| anyhow!(NotFoundError("could not find epic in database!")))?
             .status = status;
 
         self.database.write_db(&parsed)?;
         Ok(())
     }
 
+    // Fails with BlockedError when starting a story that is waiting on other stories
     pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
         let mut parsed = self.database.read_db()?;
+        self.check_story_in_project(&parsed, story_id)?;
+
+        parsed.check_can_start(story_id, &status)?;
+        parsed.set_story_status(story_id, status, today())?;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    // update_story_status() for when the user has been warned that the story is blocked
+    pub fn force_story_status(&self, story_id: u32, status: Status) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+        self.check_story_in_project(&parsed, story_id)?;
+
+        parsed.set_story_status(story_id, status, today())?;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    // None removes the due date
+    pub fn update_epic_due_date(&self, epic_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
+
+        parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find epic in database!")))?
+            .due_date = due_date;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    pub fn update_story_due_date(&self, story_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+        self.check_story_in_project(&parsed, story_id)?;
 
         parsed
             .stories
             .get_mut(&story_id)
-            .ok_or_else(|| anyhow!("could not find story in database!"))?
-            .status = status;
+            .ok_or_else(|| anyhow!(NotFoundError("could not find story in database!")))?
+            .due_date = due_date;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    // replaces the epic's name, description and status, the epic keeps its stories
+    pub fn update_epic(&self, epic_id: u32, epic: Epic) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+        self.check_epic_in_project(&parsed, epic_id)?;
+
+        let existing = parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find epic in database!")))?;
+        existing.name = epic.name;
+        existing.description = epic.description;
+        existing.status = epic.status;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    // replaces the story's name, description and status, the story keeps its linked commits
+    pub fn update_story(&self, story_id: u32, story: Story) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+        self.check_story_in_project(&parsed, story_id)?;
+
+        parsed.check_can_start(story_id, &story.status)?;
+        parsed.set_story_status(story_id, story.status, today())?;
+        let existing = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find story in database!")))?;
+        existing.name = story.name;
+        existing.description = story.description;
+
+        self.database.write_db(&parsed)?;
+        Ok(())
+    }
+
+    // new sprints go into the current project
+    pub fn create_sprint(&self, mut sprint: Sprint) -> Result<u32> {
+        let mut parsed = self.database.read_db()?;
+
+        sprint.project_id = self.current_project.get();
+        let new_id = parsed.add_sprint(sprint);
+
+        self.database.write_db(&parsed)?;
+        Ok(new_id)
+    }
+
+    pub fn add_story_to_sprint(&self, sprint_id: u32, story_id: u32) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_sprint_in_project(db_state, sprint_id)?;
+            self.check_story_in_project(db_state, story_id)?;
+            db_state.add_story_to_sprint(sprint_id, story_id)
+        })
+    }
+
+    pub fn remove_story_from_sprint(&self, sprint_id: u32, story_id: u32) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_sprint_in_project(db_state, sprint_id)?;
+            self.check_story_in_project(db_state, story_id)?;
+            db_state.remove_story_from_sprint(sprint_id, story_id)
+        })
+    }
+
+    // returns how many stories were carried over
+    pub fn close_sprint(&self, sprint_id: u32, carry_over_to: Option<u32>) -> Result<usize> {
+        self.update_batch(|db_state| {
+            self.check_sprint_in_project(db_state, sprint_id)?;
+            if let Some(next_id) = carry_over_to {
+                self.check_sprint_in_project(db_state, next_id)?;
+            }
+            db_state.close_sprint(sprint_id, carry_over_to)
+        })
+    }
+
+    pub fn add_story_link(&self, story_id: u32, kind: LinkKind, other_id: u32) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_story_in_project(db_state, story_id)?;
+            self.check_story_in_project(db_state, other_id)?;
+            db_state.add_story_link(story_id, kind, other_id)
+        })
+    }
+
+    pub fn remove_story_link(&self, story_id: u32, other_id: u32) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_story_in_project(db_state, story_id)?;
+            self.check_story_in_project(db_state, other_id)?;
+            db_state.remove_story_link(story_id, other_id)
+        })
+    }
+
+    pub fn add_worklog(&self, worklog: Worklog) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_story_in_project(db_state, worklog.story_id)?;
+            db_state.add_worklog(worklog)
+        })
+    }
+
+    pub fn start_timer(&self, story_id: u32, user: &str) -> Result<()> {
+        self.update_batch(|db_state| {
+            self.check_story_in_project(db_state, story_id)?;
+            db_state.start_timer(story_id, user, Local::now())
+        })
+    }
+
+    // returns the time that was logged
+    pub fn stop_timer(&self, story_id: u32, user: &str) -> Result<Worklog> {
+        self.update_batch(|db_state| {
+            self.check_story_in_project(db_state, story_id)?;
+            db_state.stop_timer(story_id, user, Local::now())
+        })
+    }
+
+    pub fn get_sort_order(&self, page: ListPage) -> Result<SortOrder> {
+        let parsed = self.database.read_db()?;
+
+        Ok(parsed.get_sort_order(page))
+    }
+
+    pub fn update_sort_order(&self, page: ListPage, sort_order: SortOrder) -> Result<()> {
+        let mut parsed = self.database.read_db()?;
+
+        parsed.sort_orders.insert(page, sort_order);
 
         self.database.write_db(&parsed)?;
         Ok(())
@@ -120,6 +474,10 @@ impl JiraDatabase {
 pub trait Database {
     fn read_db(&self) -> Result<DBState>;
     fn write_db(&self, db_state: &DBState) -> Result<()>;
+
+    fn take_hook_output(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 struct JSONFileDatabase {
@@ -139,8 +497,9 @@ impl Database for JSONFileDatabase {
     }
 }
 
+#[cfg(test)]
 pub mod test_utils {
-    use std::{cell::RefCell, collections::HashMap};
+    use std::cell::RefCell;
 
     use super::*;
 
@@ -151,11 +510,7 @@ pub mod test_utils {
     impl MockDB {
         pub fn new() -> Self {
             Self {
-                last_written_state: RefCell::new(DBState {
-                    last_item_id: 0,
-                    epics: HashMap::new(),
-                    stories: HashMap::new(),
-                }),
+                last_written_state: RefCell::new(DBState::default()),
             }
         }
     }
@@ -172,23 +527,27 @@ pub mod test_utils {
             Ok(())
         }
     }
+
+    // a day in 2024, the year test fixtures are set in
+    pub fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::test_utils::MockDB;
+    use super::test_utils::{date, MockDB};
     use super::*;
+    use crate::models::{BlockedError, Project, SortDirection, SortKey};
 
     #[test]
     fn create_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic.clone());
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let id = result.unwrap();
         let db_state = db.read_db().unwrap();
@@ -202,32 +561,28 @@ mod tests {
 
     #[test]
     fn create_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let story = Story::new("".to_owned(), "".to_owned());
 
         let non_existent_epic_id = 999;
 
         let result = db.create_story(story, non_existent_epic_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn create_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story.clone(), epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let id = result.unwrap();
         let db_state = db.read_db().unwrap();
@@ -236,45 +591,195 @@ mod tests {
 
         assert_eq!(id, expected_id);
         assert_eq!(db_state.last_item_id, expected_id);
+        assert!(db_state.epics.get(&epic_id).unwrap().stories.contains(&id));
+        assert_eq!(db_state.stories.get(&id), Some(&story));
+    }
+
+    #[test]
+    fn current_project_should_scope_epics_and_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let project_id = db
+            .create_project(Project::new("".to_owned(), "WEB".to_owned()))
+            .unwrap();
+
+        db.set_current_project(Some(project_id));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story = Story::new("".to_owned(), "".to_owned());
+        assert!(db.create_story(story.clone(), other_epic_id).is_err());
+        assert!(db.create_story(story, epic_id).is_ok());
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.keys().collect::<Vec<_>>(), vec![&epic_id]);
+        assert_eq!(db_state.projects[&project_id].epics, vec![epic_id]);
+
+        db.set_current_project(None);
+        assert_eq!(db.read_db().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn current_project_should_scope_updates_and_deletes() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let other_epic_id = db
+            .create_epic(Epic::new("Other".to_owned(), "".to_owned()))
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+            .unwrap();
+        let other_sprint_id = db
+            .create_sprint(Sprint::new(
+                "".to_owned(),
+                "".to_owned(),
+                date(1, 1),
+                date(1, 14),
+            ))
+            .unwrap();
+        db.add_worklog(Worklog {
+            story_id: other_story_id,
+            user: "ann".to_owned(),
+            date: date(1, 2),
+            minutes: 30,
+            note: "".to_owned(),
+            story_name: "".to_owned(),
+        })
+        .unwrap();
+        let project_id = db
+            .create_project(Project::new("".to_owned(), "WEB".to_owned()))
+            .unwrap();
+        db.set_current_project(Some(project_id));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let sprint_id = db
+            .create_sprint(Sprint::new(
+                "".to_owned(),
+                "".to_owned(),
+                date(1, 1),
+                date(1, 14),
+            ))
+            .unwrap();
+
+        let is_not_found = |result: Result<()>| {
+            result.is_err_and(|error| error.downcast_ref::<NotFoundError>().is_some())
+        };
+        let epic = Epic::new("".to_owned(), "".to_owned());
+        let story = Story::new("".to_owned(), "".to_owned());
+        assert!(is_not_found(db.move_epic(other_epic_id, project_id)));
+        assert!(is_not_found(db.update_epic(other_epic_id, epic.clone())));
+        assert!(is_not_found(
+            db.update_epic_status(other_epic_id, Status::Closed)
+        ));
+        assert!(is_not_found(db.update_epic_due_date(other_epic_id, None)));
+        assert!(is_not_found(db.update_story(other_story_id, story.clone())));
+        assert!(is_not_found(
+            db.update_story_status(other_story_id, Status::Closed)
+        ));
+        assert!(is_not_found(
+            db.force_story_status(other_story_id, Status::Closed)
+        ));
+        assert!(is_not_found(db.update_story_due_date(other_story_id, None)));
+        assert!(is_not_found(db.add_story_link(
+            story_id,
+            LinkKind::Blocks,
+            other_story_id
+        )));
+        assert!(is_not_found(db.start_timer(other_story_id, "ann")));
+        assert!(is_not_found(
+            db.add_story_to_sprint(sprint_id, other_story_id)
+        ));
+        assert!(is_not_found(
+            db.add_story_to_sprint(other_sprint_id, story_id)
+        ));
+        assert!(db.close_sprint(other_sprint_id, None).is_err());
+        assert!(db.close_sprint(sprint_id, Some(other_sprint_id)).is_err());
+        assert!(is_not_found(db.delete_story(other_epic_id, other_story_id)));
+        assert!(is_not_found(db.delete_epic(other_epic_id)));
+        assert!(is_not_found(db.update_batch(|db_state| {
+            db_state.stories.remove(&other_story_id);
+            Ok(())
+        })));
+        assert!(is_not_found(db.update_batch(|db_state| {
+            db_state.sprints.remove(&other_sprint_id);
+            Ok(())
+        })));
+        assert!(is_not_found(db.update_batch(|db_state| {
+            db_state.worklogs.clear();
+            Ok(())
+        })));
+        assert!(is_not_found(db.update_batch(|db_state| {
+            db_state.status_changes.clear();
+            Ok(())
+        })));
+
+        assert!(db.update_epic(epic_id, epic).is_ok());
+        assert!(db.update_story(story_id, story).is_ok());
+        assert!(db.add_story_to_sprint(sprint_id, story_id).is_ok());
+        let new_epic_id = db
+            .update_batch(|db_state| Ok(db_state.add_epic(Epic::new("".to_owned(), "".to_owned()))))
+            .unwrap();
+        let new_sprint_id = db
+            .update_batch(|db_state| {
+                let sprint = Sprint::new("".to_owned(), "".to_owned(), date(1, 15), date(1, 28));
+                Ok(db_state.add_sprint(sprint))
+            })
+            .unwrap();
+        assert_eq!(db.close_sprint(sprint_id, Some(new_sprint_id)).unwrap(), 1);
+        assert!(db.delete_story(epic_id, story_id).is_ok());
+        assert!(db.delete_epic(epic_id).is_ok());
+        let db_state = db.read_db().unwrap();
         assert_eq!(
-            db_state.epics.get(&epic_id).unwrap().stories.contains(&id),
-            true
+            db_state.epics.keys().collect::<Vec<_>>(),
+            vec![&new_epic_id]
         );
-        assert_eq!(db_state.stories.get(&id), Some(&story));
+        let mut sprint_ids: Vec<_> = db_state.sprints.keys().collect();
+        sprint_ids.sort();
+        assert_eq!(sprint_ids, vec![&sprint_id, &new_sprint_id]);
+
+        db.set_current_project(None);
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&other_epic_id].name, "Other");
+        assert_eq!(db_state.epics[&other_epic_id].status, Status::Open);
+        assert_eq!(db_state.stories[&other_story_id].status, Status::Open);
+        assert_eq!(db_state.sprints[&other_sprint_id].project_id, None);
+        assert!(!db_state.sprints[&other_sprint_id].closed);
+        assert_eq!(db_state.worklogs.len(), 1);
     }
 
     #[test]
     fn delete_epic_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let non_existent_epic_id = 999;
 
         let result = db.delete_epic(non_existent_epic_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let result = db.delete_epic(epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
@@ -287,116 +792,103 @@ mod tests {
 
     #[test]
     fn delete_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let non_existent_epic_id = 999;
 
         let result = db.delete_story(non_existent_epic_id, story_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_story_should_error_if_story_not_found_in_epic() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let non_existent_story_id = 999;
 
         let result = db.delete_story(epic_id, non_existent_story_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let result = db.delete_story(epic_id, story_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
         let expected_last_id = 2;
 
         assert_eq!(db_state.last_item_id, expected_last_id);
-        assert_eq!(
-            db_state
-                .epics
-                .get(&epic_id)
-                .unwrap()
-                .stories
-                .contains(&story_id),
-            false
-        );
+        assert!(!db_state
+            .epics
+            .get(&epic_id)
+            .unwrap()
+            .stories
+            .contains(&story_id));
         assert_eq!(db_state.stories.get(&story_id), None);
     }
 
     #[test]
     fn update_epic_status_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let non_existent_epic_id = 999;
 
         let result = db.update_epic_status(non_existent_epic_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn update_epic_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.update_epic_status(epic_id, Status::Closed);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
@@ -405,21 +897,17 @@ mod tests {
 
     #[test]
     fn update_story_status_should_error_if_invalid_story_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let non_existent_story_id = 999;
 
         let result = db.update_story_status(non_existent_story_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn update_story_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
@@ -433,7 +921,7 @@ mod tests {
 
         let result = db.update_story_status(story_id, Status::Closed);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
@@ -443,6 +931,223 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_epic_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+
+        let result = db.update_epic(999, Epic::new("".to_owned(), "".to_owned()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn update_epic_should_keep_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut epic = Epic::new("name".to_owned(), "description".to_owned());
+        epic.status = Status::Resolved;
+        let result = db.update_epic(epic_id, epic);
+
+        assert!(result.is_ok());
+
+        let db_state = db.read_db().unwrap();
+        let epic = db_state.epics.get(&epic_id).unwrap();
+
+        assert_eq!(epic.name, "name");
+        assert_eq!(epic.description, "description");
+        assert_eq!(epic.status, Status::Resolved);
+        assert_eq!(epic.stories, vec![story_id]);
+    }
+
+    #[test]
+    fn update_story_should_work() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let story = Story::new("name".to_owned(), "description".to_owned());
+        let result = db.update_story(story_id, story.clone());
+
+        assert!(result.is_ok());
+        assert!(db.update_story(999, story.clone()).is_err());
+
+        let db_state = db.read_db().unwrap();
+
+        assert_eq!(db_state.stories.get(&story_id), Some(&story));
+    }
+
+    #[test]
+    fn update_story_status_should_refuse_to_start_blocked_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_story_link(story_id, LinkKind::BlockedBy, blocker_id)
+            .unwrap();
+
+        let error = db
+            .update_story_status(story_id, Status::InProgress)
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<BlockedError>(),
+            Some(&BlockedError {
+                story_id,
+                blockers: vec![blocker_id]
+            })
+        );
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.status = Status::InProgress;
+        assert!(db.update_story(story_id, story.clone()).is_err());
+        db.update_story_status(story_id, Status::Resolved).unwrap();
+
+        db.force_story_status(story_id, Status::InProgress).unwrap();
+        db.update_story(story_id, story).unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn update_batch_should_write_all_or_nothing() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+
+        let result = db.update_batch(|db_state| {
+            let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+            db_state.add_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+        });
+
+        assert!(result.is_ok());
+
+        let result = db.update_batch(|db_state| {
+            db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+            db_state.add_story(Story::new("".to_owned(), "".to_owned()), 999)
+        });
+
+        assert!(result.is_err());
+
+        let db_state = db.read_db().unwrap();
+
+        assert_eq!(db_state.last_item_id, 2);
+        assert_eq!(db_state.epics.len(), 1);
+        assert_eq!(db_state.stories.len(), 1);
+    }
+
+    #[test]
+    fn get_sort_order_should_default_to_id_ascending() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+
+        let result = db.get_sort_order(ListPage::Home);
+
+        assert_eq!(result.unwrap(), SortOrder::default());
+    }
+
+    #[test]
+    fn update_sort_order_should_work() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let sort_order = SortOrder {
+            key: SortKey::Status,
+            direction: SortDirection::Descending,
+        };
+
+        let result = db.update_sort_order(ListPage::EpicDetail, sort_order);
+
+        assert!(result.is_ok());
+        assert_eq!(db.get_sort_order(ListPage::EpicDetail).unwrap(), sort_order);
+        assert_eq!(
+            db.get_sort_order(ListPage::Home).unwrap(),
+            SortOrder::default()
+        );
+    }
+
+    #[test]
+    fn story_status_changes_should_be_logged() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.status = Status::Resolved;
+        db.update_story(story_id, story).unwrap();
+
+        let changes: Vec<_> = db
+            .read_db()
+            .unwrap()
+            .status_changes
+            .into_iter()
+            .map(|change| (change.story_id, change.date, change.from, change.to))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (story_id, today(), None, Status::Open),
+                (story_id, today(), Some(Status::Open), Status::InProgress),
+                (
+                    story_id,
+                    today(),
+                    Some(Status::InProgress),
+                    Status::Resolved
+                ),
+            ]
+        );
+
+        db.delete_story(epic_id, story_id).unwrap();
+        assert!(db.read_db().unwrap().status_changes.is_empty());
+    }
+
+    #[test]
+    fn sprints_should_drop_deleted_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_ids: Vec<u32> = (0..2)
+            .map(|_| {
+                db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                    .unwrap()
+            })
+            .collect();
+        let sprint_id = db
+            .create_sprint(Sprint::new("".to_owned(), "".to_owned(), date, date))
+            .unwrap();
+        for story_id in &story_ids {
+            db.add_story_to_sprint(sprint_id, *story_id).unwrap();
+        }
+        assert!(db.add_story_to_sprint(sprint_id, 999).is_err());
+
+        db.delete_story(epic_id, story_ids[0]).unwrap();
+        assert_eq!(
+            db.read_db().unwrap().sprints[&sprint_id].stories,
+            vec![story_ids[1]]
+        );
+
+        db.delete_epic(epic_id).unwrap();
+        assert!(db.read_db().unwrap().sprints[&sprint_id].stories.is_empty());
+    }
+
     mod database {
         use std::collections::HashMap;
         use std::io::Write;
@@ -454,7 +1159,7 @@ mod tests {
             let db = JSONFileDatabase {
                 file_path: "INVALID_PATH".to_owned(),
             };
-            assert_eq!(db.read_db().is_err(), true);
+            assert!(db.read_db().is_err());
         }
 
         #[test]
@@ -474,7 +1179,7 @@ mod tests {
 
             let result = db.read_db();
 
-            assert_eq!(result.is_err(), true);
+            assert!(result.is_err());
         }
 
         #[test]
@@ -494,7 +1199,7 @@ mod tests {
 
             let result = db.read_db();
 
-            assert_eq!(result.is_ok(), true);
+            assert!(result.is_ok());
         }
 
         #[test]
@@ -516,12 +1221,15 @@ mod tests {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
+                due_date: None,
+                commits: vec![],
             };
             let epic = Epic {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
                 stories: vec![2],
+                due_date: None,
             };
 
             let mut stories = HashMap::new();
@@ -534,12 +1242,13 @@ mod tests {
                 last_item_id: 2,
                 epics,
                 stories,
+                ..Default::default()
             };
 
             let write_result = db.write_db(&state);
             let read_result = db.read_db().unwrap();
 
-            assert_eq!(write_result.is_ok(), true);
+            assert!(write_result.is_ok());
             assert_eq!(read_result, state);
         }
     }
@@ -0,0 +1,265 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::db::Database;
+use crate::models::{DBState, ListPage, SortOrder};
+
+const EPICS_DIR: &str = "epics";
+const STORIES_DIR: &str = "stories";
+const SPRINTS_DIR: &str = "sprints";
+const PROJECTS_DIR: &str = "projects";
+const SORT_ORDERS_FILE: &str = "sort_orders.json";
+const STATUS_CHANGES_FILE: &str = "status_changes.json";
+const WORKLOGS_FILE: &str = "worklogs.json";
+const TIMERS_FILE: &str = "timers.json";
+const STORY_LINKS_FILE: &str = "story_links.json";
+
+// New ids start from a random six-digit number so items created on different branches don't get
+// the same id. ID_WINDOW ids after the start must be free so a batch of new items usually gets
+// consecutive ids, larger batches skip over the ids that are taken
+const MIN_ID: u32 = 100_000;
+const MAX_ID: u32 = 999_999;
+const ID_WINDOW: u32 = 1_000;
+
+// Stores each epic and story in its own file, e.g. epics/12.json, so changes to different items
+// never touch the same file and merge cleanly in git. Files are pretty-printed with their keys
+// sorted so the diff of a change only shows the fields that changed
+pub struct DirectoryDatabase {
+    pub dir: PathBuf,
+}
+
+impl Database for DirectoryDatabase {
+    fn read_db(&self) -> Result<DBState> {
+        let epics = read_items(&self.dir.join(EPICS_DIR))?;
+        let stories = read_items(&self.dir.join(STORIES_DIR))?;
+        let sprints = read_items(&self.dir.join(SPRINTS_DIR))?;
+        let projects = read_items(&self.dir.join(PROJECTS_DIR))?;
+        let sort_orders: HashMap<ListPage, SortOrder> =
+            read_optional_file(&self.dir.join(SORT_ORDERS_FILE))?;
+
+        let mut db_state = DBState {
+            last_item_id: 0,
+            epics,
+            stories,
+            sort_orders,
+            sprints,
+            status_changes: read_optional_file(&self.dir.join(STATUS_CHANGES_FILE))?,
+            worklogs: read_optional_file(&self.dir.join(WORKLOGS_FILE))?,
+            timers: read_optional_file(&self.dir.join(TIMERS_FILE))?,
+            story_links: read_optional_file(&self.dir.join(STORY_LINKS_FILE))?,
+            projects,
+        };
+        db_state.last_item_id = get_random_start_id(&db_state)?;
+        Ok(db_state)
+    }
+
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        write_items(&self.dir.join(EPICS_DIR), &db_state.epics)?;
+        write_items(&self.dir.join(STORIES_DIR), &db_state.stories)?;
+        write_items(&self.dir.join(SPRINTS_DIR), &db_state.sprints)?;
+        write_items(&self.dir.join(PROJECTS_DIR), &db_state.projects)?;
+        write_file(&self.dir.join(SORT_ORDERS_FILE), &db_state.sort_orders)?;
+        write_file(
+            &self.dir.join(STATUS_CHANGES_FILE),
+            &db_state.status_changes,
+        )?;
+        write_file(&self.dir.join(WORKLOGS_FILE), &db_state.worklogs)?;
+        write_file(&self.dir.join(TIMERS_FILE), &db_state.timers)?;
+        write_file(&self.dir.join(STORY_LINKS_FILE), &db_state.story_links)
+    }
+}
+
+// picks where next_item_id() counts up from, trying again if the window overlaps existing items
+fn get_random_start_id(db_state: &DBState) -> Result<u32> {
+    for _ in 0..100 {
+        let start = MIN_ID + (get_random_number() % u64::from(MAX_ID - MIN_ID - ID_WINDOW)) as u32;
+        let is_free = (start + 1..=start + ID_WINDOW).all(|id| !db_state.is_item_id_used(id));
+        if is_free {
+            return Ok(start);
+        }
+    }
+    Err(anyhow!("could not find free ids for new items"))
+}
+
+// RandomState is seeded randomly for each instance, which is enough for picking ids
+fn get_random_number() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+fn read_items<T: DeserializeOwned>(dir: &Path) -> Result<HashMap<u32, T>> {
+    let mut items = HashMap::new();
+    if !dir.exists() {
+        return Ok(items);
+    }
+    for entry in fs::read_dir(dir).with_context(|| anyhow!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(id) = get_item_id(&path) else {
+            continue;
+        };
+        items.insert(id, read_file(&path)?);
+    }
+    Ok(items)
+}
+
+// the id of an item file e.g. 12 for "12.json", None for anything else in the directory
+fn get_item_id(path: &Path) -> Option<u32> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+// Writes the files that changed and removes the files of items that are gone
+fn write_items<T: Serialize>(dir: &Path, items: &HashMap<u32, T>) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| anyhow!("failed to create {}", dir.display()))?;
+    for (id, item) in items {
+        write_file(&dir.join(format!("{}.json", id)), item)?;
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if get_item_id(&path).is_some_and(|id| !items.contains_key(&id)) {
+            fs::remove_file(&path)
+                .with_context(|| anyhow!("failed to remove {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn read_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content =
+        fs::read_to_string(path).with_context(|| anyhow!("failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| anyhow!("failed to parse {}", path.display()))
+}
+
+// files that aren't there yet, such as in directories written before they were added, are empty
+fn read_optional_file<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    read_file(path)
+}
+
+// Going through Value sorts the keys, serde_json's maps are ordered by key. Unchanged files aren't
+// rewritten so their modification time only changes with their content
+fn write_file(path: &Path, item: &impl Serialize) -> Result<()> {
+    let content = format_json(&serde_json::to_value(item)?)?;
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    fs::write(path, content).with_context(|| anyhow!("failed to write {}", path.display()))
+}
+
+pub fn format_json(value: &serde_json::Value) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)? + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_import::{import_csv, ColumnMapping};
+    use crate::db::JiraDatabase;
+    use crate::models::{Epic, SortDirection, SortKey, Status, Story};
+
+    fn get_db(dir: &Path) -> JiraDatabase {
+        JiraDatabase::from_database(Box::new(DirectoryDatabase {
+            dir: dir.to_path_buf(),
+        }))
+    }
+
+    #[test]
+    fn directory_database_should_store_a_file_per_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = get_db(dir.path());
+        assert!(db.read_db().unwrap().epics.is_empty());
+
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.update_sort_order(
+            ListPage::Home,
+            SortOrder {
+                key: SortKey::Name,
+                direction: SortDirection::Descending,
+            },
+        )
+        .unwrap();
+
+        assert!((MIN_ID..=MAX_ID).contains(&epic_id));
+        let story_file = dir.path().join(format!("stories/{}.json", story_id));
+        assert_eq!(
+            fs::read_to_string(&story_file).unwrap(),
+            "{\n  \"description\": \"\",\n  \"name\": \"Story\",\n  \"status\": \"InProgress\"\n}\n"
+        );
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].stories, vec![story_id]);
+        assert_eq!(db_state.stories[&story_id].status, Status::InProgress);
+        assert_eq!(
+            db.get_sort_order(ListPage::Home).unwrap().key,
+            SortKey::Name
+        );
+
+        db.delete_story(epic_id, story_id).unwrap();
+        assert!(!story_file.exists());
+        assert!(dir.path().join(format!("epics/{}.json", epic_id)).exists());
+    }
+
+    #[test]
+    fn get_random_start_id_should_leave_room_for_new_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = get_db(dir.path());
+        let mut db_state = db.read_db().unwrap();
+        let ids: Vec<u32> = (0..ID_WINDOW).map(|_| db_state.next_item_id()).collect();
+
+        for id in ids {
+            db_state
+                .epics
+                .insert(id, Epic::new("".to_owned(), "".to_owned()));
+        }
+        let start = get_random_start_id(&db_state).unwrap();
+
+        assert!((start + 1..=start + ID_WINDOW).all(|id| !db_state.epics.contains_key(&id)));
+    }
+
+    #[test]
+    fn read_db_should_not_reuse_ids_past_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = get_db(dir.path());
+        let mut db_state = db.read_db().unwrap();
+        let existing_id = db_state.last_item_id + ID_WINDOW + 1;
+        db_state
+            .epics
+            .insert(existing_id, Epic::new("Existing".to_owned(), "".to_owned()));
+
+        let csv = (0..=ID_WINDOW).fold("type,name\n".to_owned(), |csv, i| {
+            csv + &format!("epic,Epic {}\n", i)
+        });
+        import_csv(&mut db_state, csv.as_bytes(), &ColumnMapping::default()).unwrap();
+
+        assert_eq!(db_state.epics.len(), ID_WINDOW as usize + 2);
+        assert_eq!(db_state.epics[&existing_id].name, "Existing");
+    }
+
+    #[test]
+    fn read_db_should_ignore_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(EPICS_DIR)).unwrap();
+        fs::write(dir.path().join("epics/README.md"), "notes").unwrap();
+        fs::write(dir.path().join("epics/7.json"), "{").unwrap();
+
+        assert!(get_db(dir.path()).read_db().is_err());
+        fs::remove_file(dir.path().join("epics/7.json")).unwrap();
+        assert!(get_db(dir.path()).read_db().unwrap().epics.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
+
+pub const DUE_DATE_HELP: &str = "YYYY-MM-DD, +3d, +2w, today, tomorrow or a weekday";
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+// Reads an ISO date or one relative to today: "+3d", "+2w", "today", "tomorrow" or a weekday
+// such as "friday", which is the next one after today
+pub fn parse_due_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let error = || anyhow!("invalid due date: {} (use {})", input, DUE_DATE_HELP);
+
+    let days = match input.as_str() {
+        "today" => Some(0),
+        "tomorrow" => Some(1),
+        _ => None,
+    };
+    if let Some(days) = days {
+        return today.checked_add_days(Days::new(days)).ok_or_else(error);
+    }
+
+    if let Some(offset) = input.strip_prefix('+') {
+        let unit = offset.chars().next_back().ok_or_else(error)?;
+        let count: u64 = offset[..offset.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| error())?;
+        let days = match unit {
+            'd' => count,
+            'w' => count.checked_mul(7).ok_or_else(error)?,
+            _ => return Err(error()),
+        };
+        return today.checked_add_days(Days::new(days)).ok_or_else(error);
+    }
+
+    if let Ok(weekday) = input.parse::<Weekday>() {
+        let days_ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday() - 1) % 7
+                + 1;
+        return today
+            .checked_add_days(Days::new(days_ahead.into()))
+            .ok_or_else(error);
+    }
+
+    NaiveDate::parse_from_str(&input, "%Y-%m-%d").map_err(|_| error())
+}
+
+// how many days past due_date today is, 0 or less when it isn't late yet
+pub fn get_days_late(due_date: NaiveDate, today: NaiveDate) -> i64 {
+    (today - due_date).num_days()
+}
+
+// the Monday of the week date is in
+pub fn get_week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse_due_date_should_accept_iso_and_relative_dates() {
+        // a Wednesday
+        let today = date(2024, 1, 31);
+
+        let cases = [
+            ("2024-03-01", date(2024, 3, 1)),
+            ("today", today),
+            ("Tomorrow", date(2024, 2, 1)),
+            ("+3d", date(2024, 2, 3)),
+            ("+2w", date(2024, 2, 14)),
+            ("friday", date(2024, 2, 2)),
+            ("wed", date(2024, 2, 7)),
+            ("tuesday", date(2024, 2, 6)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_due_date(input, today).unwrap(), expected, "{}", input);
+        }
+
+        for input in ["", "+d", "+3m", "+3é", "2024-02-30", "someday"] {
+            assert!(parse_due_date(input, today).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn get_days_late_should_count_days() {
+        assert_eq!(get_days_late(date(2024, 1, 29), date(2024, 2, 1)), 3);
+        assert_eq!(get_days_late(date(2024, 2, 1), date(2024, 2, 1)), 0);
+    }
+
+    #[test]
+    fn get_week_start_should_return_the_monday() {
+        assert_eq!(get_week_start(date(2024, 1, 31)), date(2024, 1, 29));
+        assert_eq!(get_week_start(date(2024, 1, 29)), date(2024, 1, 29));
+        assert_eq!(get_week_start(date(2024, 2, 4)), date(2024, 1, 29));
+    }
+}
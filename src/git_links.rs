@@ -0,0 +1,322 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::due_date::today;
+use crate::models::{Commit, DBState, Status};
+
+// words that mark a reference as finishing the story e.g. "fixes #9"
+const RESOLVE_KEYWORDS: [&str; 9] = [
+    "fix", "fixes", "fixed", "close", "closes", "closed", "resolve", "resolves", "resolved",
+];
+
+// fields are separated by the unit separator and commits by the record separator, neither of
+// which turns up in commit messages
+const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%aI%x1f%s%x1f%B%x1e";
+
+// The repository to scan when none is given on the command line
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct GitConfig {
+    pub repository: String,
+    // move stories to RESOLVED when a commit says it fixes them
+    #[serde(default)]
+    pub resolve: bool,
+}
+
+impl GitConfig {
+    // reads the config file if there is one, otherwise scans the current directory
+    pub fn load(file_path: &str) -> Result<Self> {
+        if !Path::new(file_path).exists() {
+            return Ok(GitConfig {
+                repository: ".".to_owned(),
+                resolve: false,
+            });
+        }
+        let content = fs::read_to_string(file_path)
+            .with_context(|| anyhow!("failed to read git config: {}", file_path))?;
+        serde_json::from_str(&content)
+            .with_context(|| anyhow!("failed to parse git config: {}", file_path))
+    }
+}
+
+pub struct LoggedCommit {
+    pub commit: Commit,
+    // the whole message, references can be in the body as well as the subject
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub story_id: u32,
+    pub resolves: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LinkSummary {
+    pub commits_linked: usize,
+    // stories moved to RESOLVED because a commit says it fixes them
+    pub stories_resolved: BTreeSet<u32>,
+    // stories with fixing commits that are left for a scan with resolve on
+    pub stories_to_resolve: BTreeSet<u32>,
+}
+
+// Every commit reachable from HEAD, oldest first
+pub fn read_commits(repository: &Path) -> Result<Vec<LoggedCommit>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(["log", "--reverse", LOG_FORMAT])
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git log failed in {}: {}",
+            repository.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_log(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_log(log: &str) -> Result<Vec<LoggedCommit>> {
+    log.split('\x1e')
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record.splitn(5, '\x1f').collect();
+            match fields.as_slice() {
+                [hash, author, date, subject, message] => Ok(LoggedCommit {
+                    commit: Commit {
+                        hash: hash.to_string(),
+                        author: author.to_string(),
+                        date: date.to_string(),
+                        subject: subject.to_string(),
+                        resolve_pending: false,
+                    },
+                    message: message.to_string(),
+                }),
+                _ => Err(anyhow!("unexpected git log output: {}", record)),
+            }
+        })
+        .collect()
+}
+
+// Finds "#<id>" in the message, the # must not follow a letter or digit so "abc#1" isn't one
+pub fn get_references(message: &str) -> Vec<Reference> {
+    let mut references = Vec::new();
+    for (i, _) in message.match_indices('#') {
+        if message[..i]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+        let digits: String = message[i + 1..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let Ok(story_id) = digits.parse() else {
+            continue;
+        };
+
+        let previous_word = message[..i]
+            .trim_end()
+            .trim_end_matches(':')
+            .split_whitespace()
+            .next_back()
+            .unwrap_or_default()
+            .to_lowercase();
+        references.push(Reference {
+            story_id,
+            resolves: RESOLVE_KEYWORDS.contains(&previous_word.as_str()),
+        });
+    }
+    references
+}
+
+// Adds each commit to the stories it mentions, skipping commits a story already has so the history
+// can be scanned again. References to ids that aren't stories are ignored.
+// A fixing commit resolves its story once, when resolve is on, even if it was linked by an earlier
+// scan. A story reopened after that stays open
+pub fn link_commits(
+    db_state: &mut DBState,
+    commits: &[LoggedCommit],
+    resolve: bool,
+) -> Result<LinkSummary> {
+    let mut summary = LinkSummary::default();
+    for LoggedCommit { commit, message } in commits {
+        for reference in get_references(message) {
+            let Some(story) = db_state.stories.get_mut(&reference.story_id) else {
+                continue;
+            };
+            if !story
+                .commits
+                .iter()
+                .any(|linked| linked.hash == commit.hash)
+            {
+                story.commits.push(Commit {
+                    resolve_pending: reference.resolves && !story.status.is_done(),
+                    ..commit.clone()
+                });
+                summary.commits_linked += 1;
+            }
+        }
+    }
+
+    let pending: Vec<u32> = db_state
+        .stories
+        .iter()
+        .filter(|(_, story)| story.commits.iter().any(|commit| commit.resolve_pending))
+        .map(|(story_id, _)| *story_id)
+        .collect();
+    for story_id in pending {
+        if !resolve {
+            summary.stories_to_resolve.insert(story_id);
+            continue;
+        }
+        if !db_state.stories[&story_id].status.is_done() {
+            db_state.set_story_status(story_id, Status::Resolved, today())?;
+            summary.stories_resolved.insert(story_id);
+        }
+        if let Some(story) = db_state.stories.get_mut(&story_id) {
+            for commit in &mut story.commits {
+                commit.resolve_pending = false;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Story};
+
+    fn get_commit(hash: &str, message: &str) -> LoggedCommit {
+        LoggedCommit {
+            commit: Commit {
+                hash: hash.to_owned(),
+                author: "Ann".to_owned(),
+                date: "2024-01-02T03:04:05+00:00".to_owned(),
+                subject: message.lines().next().unwrap_or_default().to_owned(),
+                resolve_pending: false,
+            },
+            message: message.to_owned(),
+        }
+    }
+
+    #[test]
+    fn get_references_should_find_story_ids() {
+        assert_eq!(
+            get_references("#9 peel banana\n\nFixes: #10, closes #11 and see issue#12 or #x"),
+            vec![
+                Reference {
+                    story_id: 9,
+                    resolves: false
+                },
+                Reference {
+                    story_id: 10,
+                    resolves: true
+                },
+                Reference {
+                    story_id: 11,
+                    resolves: true
+                },
+            ]
+        );
+        assert!(get_references("no references").is_empty());
+    }
+
+    #[test]
+    fn link_commits_should_add_commits_once() {
+        let mut db_state = DBState::default();
+        let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        let story_id = db_state
+            .add_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let mut closed = Story::new("".to_owned(), "".to_owned());
+        closed.status = Status::Closed;
+        let closed_id = db_state.add_story(closed, epic_id).unwrap();
+
+        let commits = vec![
+            get_commit("aaa", &format!("#{} start", story_id)),
+            get_commit("bbb", &format!("fixes #{} and #{}", story_id, epic_id)),
+            get_commit("ccc", &format!("Closes #{}", closed_id)),
+        ];
+        let summary = link_commits(&mut db_state, &commits, true).unwrap();
+
+        assert_eq!(summary.commits_linked, 3);
+        assert_eq!(summary.stories_resolved, BTreeSet::from([story_id]));
+        assert_eq!(db_state.stories[&story_id].status, Status::Resolved);
+        let hashes: Vec<_> = db_state.stories[&story_id]
+            .commits
+            .iter()
+            .map(|commit| commit.hash.as_str())
+            .collect();
+        assert_eq!(hashes, vec!["aaa", "bbb"]);
+
+        assert_eq!(
+            link_commits(&mut db_state, &commits, true)
+                .unwrap()
+                .commits_linked,
+            0
+        );
+    }
+
+    #[test]
+    fn link_commits_should_resolve_each_fixing_commit_once() {
+        let mut db_state = DBState::default();
+        let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        let story_id = db_state
+            .add_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let commits = vec![get_commit("aaa", &format!("fixes #{}", story_id))];
+
+        // linked without resolving, then resolved by a later scan
+        let summary = link_commits(&mut db_state, &commits, false).unwrap();
+        assert_eq!(summary.stories_to_resolve, BTreeSet::from([story_id]));
+        assert_eq!(db_state.stories[&story_id].status, Status::Open);
+        let summary = link_commits(&mut db_state, &commits, true).unwrap();
+        assert_eq!(summary.stories_resolved, BTreeSet::from([story_id]));
+        assert_eq!(db_state.stories[&story_id].status, Status::Resolved);
+
+        // reopened by hand
+        db_state.stories.get_mut(&story_id).unwrap().status = Status::Open;
+        let summary = link_commits(&mut db_state, &commits, true).unwrap();
+        assert!(summary.stories_resolved.is_empty());
+        assert_eq!(db_state.stories[&story_id].status, Status::Open);
+    }
+
+    #[test]
+    fn read_commits_should_read_the_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=Ann", "-c", "user.email=ann@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "#2 first"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "second\n\nfixes #2"]);
+
+        let commits = read_commits(dir.path()).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].commit.subject, "#2 first");
+        assert_eq!(commits[1].commit.author, "Ann");
+        assert_eq!(commits[1].commit.hash.len(), 40);
+        assert_eq!(commits[1].message.trim_end(), "second\n\nfixes #2");
+        assert!(read_commits(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::Database;
+use crate::models::{DBState, Status};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    EpicCreated,
+    StoryCreated,
+    EpicStatusChanged,
+    StoryStatusChanged,
+    EpicDeleted,
+    StoryDeleted,
+}
+
+// Written to the hook's stdin as JSON
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Event {
+    pub event: EventKind,
+    pub id: u32,
+    // the epic a story belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_status: Option<Status>,
+    // the item after the change, or before it was deleted
+    pub item: Value,
+}
+
+// Shell commands to run for each event. Pre-hooks run before the change is written and can veto it
+// by exiting non-zero, post-hooks run once it has been written
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct Hooks {
+    #[serde(default)]
+    pub pre: HashMap<EventKind, Vec<String>>,
+    #[serde(default)]
+    pub post: HashMap<EventKind, Vec<String>>,
+}
+
+impl Hooks {
+    // reads the hooks file if there is one, otherwise there are no hooks
+    pub fn load(file_path: &str) -> Result<Self> {
+        if !Path::new(file_path).exists() {
+            return Ok(Hooks::default());
+        }
+        let content = fs::read_to_string(file_path)
+            .with_context(|| anyhow!("failed to read hooks file: {}", file_path))?;
+        serde_json::from_str(&content)
+            .with_context(|| anyhow!("failed to parse hooks file: {}", file_path))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre
+            .values()
+            .chain(self.post.values())
+            .all(Vec::is_empty)
+    }
+}
+
+// A change stopped by a pre-hook
+#[derive(Debug)]
+pub struct VetoError(pub String);
+
+impl Display for VetoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for VetoError {}
+
+// Wraps another database, comparing every write with the current state to find the events and
+// running their hooks around the write
+pub struct HookedDatabase {
+    pub database: Box<dyn Database>,
+    pub hooks: Hooks,
+    // stderr of hooks and failed post-hooks, until taken by take_hook_output()
+    output: RefCell<Vec<String>>,
+}
+
+impl HookedDatabase {
+    pub fn new(database: Box<dyn Database>, hooks: Hooks) -> Self {
+        Self {
+            database,
+            hooks,
+            output: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn add_output(&self, command: &str, stderr: &str) {
+        if !stderr.trim().is_empty() {
+            self.output
+                .borrow_mut()
+                .push(format!("hook {}: {}", command, stderr.trim()));
+        }
+    }
+}
+
+impl Database for HookedDatabase {
+    fn read_db(&self) -> Result<DBState> {
+        self.database.read_db()
+    }
+
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        let events = get_events(&self.database.read_db()?, db_state)?;
+
+        for event in &events {
+            for command in self.hooks.pre.get(&event.event).into_iter().flatten() {
+                let result = run_hook(command, event)?;
+                if !result.success {
+                    return Err(anyhow!(VetoError(format!(
+                        "change stopped by hook {}: {}",
+                        command,
+                        result.stderr.trim()
+                    ))));
+                }
+                self.add_output(command, &result.stderr);
+            }
+        }
+
+        self.database.write_db(db_state)?;
+
+        // the change has been made so a failing post-hook is only reported
+        for event in &events {
+            for command in self.hooks.post.get(&event.event).into_iter().flatten() {
+                match run_hook(command, event) {
+                    Ok(result) if !result.success => self.output.borrow_mut().push(format!(
+                        "hook {} failed: {}",
+                        command,
+                        result.stderr.trim()
+                    )),
+                    Ok(result) => self.add_output(command, &result.stderr),
+                    Err(e) => self.output.borrow_mut().push(format!("{:#}", e)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn take_hook_output(&self) -> Vec<String> {
+        self.output.take()
+    }
+}
+
+struct HookResult {
+    success: bool,
+    stderr: String,
+}
+
+// Runs command with sh, the event is on stdin and its kind in $JIRA_EVENT. stdout is discarded as
+// it would garble the pages and the JSON-RPC output
+fn run_hook(command: &str, event: &Event) -> Result<HookResult> {
+    let event_kind = serde_json::to_value(event.event)?;
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("JIRA_EVENT", event_kind.as_str().unwrap_or_default())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| anyhow!("failed to run hook: {}", command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // hooks don't have to read the event, so a closed pipe isn't an error
+        let _ = writeln!(stdin, "{}", serde_json::to_string(event)?);
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| anyhow!("failed to run hook: {}", command))?;
+
+    Ok(HookResult {
+        success: output.status.success(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+// Every creation, status change and deletion between two states, epics before stories and each in
+// id order
+pub fn get_events(old: &DBState, new: &DBState) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+
+    let mut epic_ids: Vec<_> = old.epics.keys().chain(new.epics.keys()).collect();
+    epic_ids.sort();
+    epic_ids.dedup();
+    for id in epic_ids {
+        let (kind, old_status, epic) = match (old.epics.get(id), new.epics.get(id)) {
+            (None, Some(epic)) => (EventKind::EpicCreated, None, epic),
+            (Some(epic), None) => (EventKind::EpicDeleted, None, epic),
+            (Some(old_epic), Some(epic)) if old_epic.status != epic.status => (
+                EventKind::EpicStatusChanged,
+                Some(old_epic.status.clone()),
+                epic,
+            ),
+            _ => continue,
+        };
+        events.push(Event {
+            event: kind,
+            id: *id,
+            epic_id: None,
+            old_status,
+            item: serde_json::to_value(epic)?,
+        });
+    }
+
+    let mut story_ids: Vec<_> = old.stories.keys().chain(new.stories.keys()).collect();
+    story_ids.sort();
+    story_ids.dedup();
+    for id in story_ids {
+        let (kind, old_status, story, db_state) = match (old.stories.get(id), new.stories.get(id)) {
+            (None, Some(story)) => (EventKind::StoryCreated, None, story, new),
+            (Some(story), None) => (EventKind::StoryDeleted, None, story, old),
+            (Some(old_story), Some(story)) if old_story.status != story.status => (
+                EventKind::StoryStatusChanged,
+                Some(old_story.status.clone()),
+                story,
+                new,
+            ),
+            _ => continue,
+        };
+        let epic_id = db_state
+            .epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(id))
+            .map(|(epic_id, _)| *epic_id);
+        events.push(Event {
+            event: kind,
+            id: *id,
+            epic_id,
+            old_status,
+            item: serde_json::to_value(story)?,
+        });
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::db::JiraDatabase;
+    use crate::models::{Epic, Story};
+
+    fn get_db(hooks: Hooks) -> JiraDatabase {
+        JiraDatabase::from_database(Box::new(HookedDatabase::new(
+            Box::new(MockDB::new()),
+            hooks,
+        )))
+    }
+
+    #[test]
+    fn get_events_should_find_changes() {
+        let mut old = MockDB::new().read_db().unwrap();
+        let epic_id = old.add_epic(Epic::new("Epic".to_owned(), "".to_owned()));
+        let story_id = old
+            .add_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        old.add_story(Story::new("Other".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut new = old.clone();
+        new.stories.get_mut(&story_id).unwrap().status = Status::Resolved;
+        new.stories.remove(&3);
+        new.epics
+            .get_mut(&epic_id)
+            .unwrap()
+            .stories
+            .retain(|id| *id != 3);
+        new.add_epic(Epic::new("New".to_owned(), "".to_owned()));
+
+        let events: Vec<_> = get_events(&old, &new)
+            .unwrap()
+            .into_iter()
+            .map(|event| (event.event, event.id, event.epic_id, event.old_status))
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                (EventKind::EpicCreated, 4, None, None),
+                (
+                    EventKind::StoryStatusChanged,
+                    2,
+                    Some(1),
+                    Some(Status::Open)
+                ),
+                (EventKind::StoryDeleted, 3, Some(1), None),
+            ]
+        );
+        assert!(get_events(&new, &new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn post_hooks_should_receive_the_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("event.json");
+        let hooks = Hooks {
+            pre: HashMap::new(),
+            post: HashMap::from([(
+                EventKind::StoryStatusChanged,
+                vec![format!(
+                    "cat > {}; echo \"$JIRA_EVENT done\" >&2",
+                    path.display()
+                )],
+            )]),
+        };
+        let db = get_db(hooks);
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        assert!(db.take_hook_output().is_empty());
+
+        db.update_story_status(story_id, Status::Resolved).unwrap();
+
+        let event: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(event["event"], "story_status_changed");
+        assert_eq!(event["epic_id"], 1);
+        assert_eq!(event["old_status"], "Open");
+        assert_eq!(event["item"]["status"], "Resolved");
+        assert_eq!(
+            db.take_hook_output(),
+            vec![format!(
+                "hook cat > {}; echo \"$JIRA_EVENT done\" >&2: story_status_changed done",
+                path.display()
+            )]
+        );
+        assert!(db.take_hook_output().is_empty());
+    }
+
+    #[test]
+    fn pre_hooks_should_veto_changes() {
+        let hooks = Hooks {
+            pre: HashMap::from([(
+                EventKind::EpicDeleted,
+                vec!["echo not allowed >&2; exit 1".to_owned()],
+            )]),
+            post: HashMap::from([(EventKind::EpicDeleted, vec!["exit 1".to_owned()])]),
+        };
+        let db = get_db(hooks);
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let error = db.delete_epic(epic_id).unwrap_err();
+
+        assert!(error.downcast_ref::<VetoError>().is_some());
+        assert_eq!(
+            error.to_string(),
+            "change stopped by hook echo not allowed >&2; exit 1: not allowed"
+        );
+        assert!(db.read_db().unwrap().epics.contains_key(&epic_id));
+        assert!(db.take_hook_output().is_empty());
+    }
+
+    #[test]
+    fn load_should_read_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.json");
+        let path = path.to_str().unwrap();
+        assert_eq!(Hooks::load(path).unwrap(), Hooks::default());
+
+        fs::write(path, r#"{ "post": { "story_created": ["./notify.sh"] } }"#).unwrap();
+        let hooks = Hooks::load(path).unwrap();
+        assert_eq!(hooks.post[&EventKind::StoryCreated], vec!["./notify.sh"]);
+        assert!(!hooks.is_empty());
+
+        fs::write(path, r#"{ "post": { "story_moved": [] } }"#).unwrap();
+        assert!(Hooks::load(path).is_err());
+    }
+}
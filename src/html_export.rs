@@ -0,0 +1,312 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::models::{DBState, Epic, Status, Story};
+
+// Everything the pages need is inline so the site works from a file share without a server or a
+// network connection
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; padding: 0 1em; color: #222; }
+nav { margin-bottom: 1em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.4em; text-align: left; }
+.description { white-space: pre-wrap; }
+.status { border-radius: 0.3em; color: #fff; font-size: 0.85em; padding: 0.1em 0.5em; white-space: nowrap; }
+.status-open { background: #1f6feb; }
+.status-in-progress { background: #bf8700; }
+.status-resolved { background: #1a7f37; }
+.status-closed { background: #6e7781; }
+#search { box-sizing: border-box; font-size: 1em; margin-bottom: 1em; padding: 0.4em; width: 100%; }
+#results { list-style: none; padding: 0; }
+";
+
+// Filters the search index embedded in the index page as the user types
+const SEARCH_SCRIPT: &str = "
+const index = JSON.parse(document.getElementById('search-index').textContent);
+const input = document.getElementById('search');
+const results = document.getElementById('results');
+input.addEventListener('input', () => {
+  const terms = input.value.toLowerCase().split(/\\s+/).filter(term => term);
+  results.replaceChildren();
+  if (terms.length === 0) return;
+  for (const entry of index) {
+    const text = [entry.kind, entry.id, entry.name, entry.description, entry.status].join(' ').toLowerCase();
+    if (!terms.every(term => text.includes(term))) continue;
+    const link = document.createElement('a');
+    link.href = entry.url;
+    link.textContent = `${entry.kind} #${entry.id}: ${entry.name} (${entry.status})`;
+    const item = document.createElement('li');
+    item.appendChild(link);
+    results.appendChild(item);
+  }
+});
+";
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    kind: &'static str,
+    id: u32,
+    name: &'a str,
+    description: &'a str,
+    status: String,
+    url: String,
+}
+
+// Writes the site into dir, creating it if needed. Returns the number of files written
+pub fn export_html(db_state: &DBState, dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir)
+        .with_context(|| anyhow!("failed to create directory: {}", dir.display()))?;
+
+    let files = get_site_files(db_state)?;
+    for (file_name, content) in &files {
+        let path = dir.join(file_name);
+        fs::write(&path, content)
+            .with_context(|| anyhow!("failed to write file: {}", path.display()))?;
+    }
+    Ok(files.len())
+}
+
+// (file name, content) for every file of the site: the index, a page per epic and story, and the
+// search index
+fn get_site_files(db_state: &DBState) -> Result<Vec<(String, String)>> {
+    let mut files = Vec::new();
+    let mut search_index = Vec::new();
+
+    let epics = db_state.get_sorted_epics();
+    for (epic_id, epic) in &epics {
+        search_index.push(SearchEntry {
+            kind: "epic",
+            id: *epic_id,
+            name: &epic.name,
+            description: &epic.description,
+            status: epic.status.to_string(),
+            url: get_epic_file_name(*epic_id),
+        });
+        files.push((
+            get_epic_file_name(*epic_id),
+            get_epic_page(db_state, *epic_id, epic)?,
+        ));
+
+        for (story_id, story) in db_state.get_sorted_stories(epic) {
+            search_index.push(SearchEntry {
+                kind: "story",
+                id: story_id,
+                name: &story.name,
+                description: &story.description,
+                status: story.status.to_string(),
+                url: get_story_file_name(story_id),
+            });
+            files.push((
+                get_story_file_name(story_id),
+                get_story_page(*epic_id, epic, story_id, story)?,
+            ));
+        }
+    }
+
+    let search_index = serde_json::to_string(&search_index)?;
+    files.insert(
+        0,
+        (
+            "index.html".to_owned(),
+            get_index_page(&epics, &search_index)?,
+        ),
+    );
+    files.push(("search-index.json".to_owned(), search_index));
+    Ok(files)
+}
+
+fn get_index_page(epics: &[(u32, &Epic)], search_index: &str) -> Result<String> {
+    let mut body = String::from("<h1>Epics</h1>\n");
+    body.push_str(
+        "<input id=\"search\" type=\"search\" placeholder=\"Search epics and stories\">\n",
+    );
+    body.push_str("<ul id=\"results\"></ul>\n");
+    body.push_str("<table>\n<tr><th>id</th><th>name</th><th>status</th></tr>\n");
+    for (epic_id, epic) in epics {
+        writeln!(
+            body,
+            "<tr><td>{}</td><td><a href=\"{}\">{}</a></td><td>{}</td></tr>",
+            epic_id,
+            get_epic_file_name(*epic_id),
+            escape_html(&epic.name),
+            get_status_badge(&epic.status)
+        )?;
+    }
+    body.push_str("</table>\n");
+    // "</" would end the script element early, JSON allows it to be escaped
+    writeln!(
+        body,
+        "<script id=\"search-index\" type=\"application/json\">{}</script>",
+        search_index.replace("</", "<\\/")
+    )?;
+    writeln!(body, "<script>{}</script>", SEARCH_SCRIPT)?;
+
+    Ok(get_page("Epics", &body))
+}
+
+fn get_epic_page(db_state: &DBState, epic_id: u32, epic: &Epic) -> Result<String> {
+    let mut body = String::from("<nav><a href=\"index.html\">All epics</a></nav>\n");
+    writeln!(
+        body,
+        "<h1>{} {}</h1>",
+        escape_html(&epic.name),
+        get_status_badge(&epic.status)
+    )?;
+    writeln!(body, "<p>Epic #{}</p>", epic_id)?;
+    writeln!(
+        body,
+        "<p class=\"description\">{}</p>",
+        escape_html(&epic.description)
+    )?;
+
+    body.push_str("<h2>Stories</h2>\n");
+    body.push_str("<table>\n<tr><th>id</th><th>name</th><th>status</th></tr>\n");
+    for (story_id, story) in db_state.get_sorted_stories(epic) {
+        writeln!(
+            body,
+            "<tr><td>{}</td><td><a href=\"{}\">{}</a></td><td>{}</td></tr>",
+            story_id,
+            get_story_file_name(story_id),
+            escape_html(&story.name),
+            get_status_badge(&story.status)
+        )?;
+    }
+    body.push_str("</table>\n");
+
+    Ok(get_page(&epic.name, &body))
+}
+
+fn get_story_page(epic_id: u32, epic: &Epic, story_id: u32, story: &Story) -> Result<String> {
+    let mut body = String::new();
+    writeln!(
+        body,
+        "<nav><a href=\"index.html\">All epics</a> / <a href=\"{}\">{}</a></nav>",
+        get_epic_file_name(epic_id),
+        escape_html(&epic.name)
+    )?;
+    writeln!(
+        body,
+        "<h1>{} {}</h1>",
+        escape_html(&story.name),
+        get_status_badge(&story.status)
+    )?;
+    writeln!(body, "<p>Story #{}</p>", story_id)?;
+    writeln!(
+        body,
+        "<p class=\"description\">{}</p>",
+        escape_html(&story.description)
+    )?;
+
+    Ok(get_page(&story.name, &body))
+}
+
+fn get_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        STYLE,
+        body
+    )
+}
+
+fn get_status_badge(status: &Status) -> String {
+    let class = match status {
+        Status::Open => "status-open",
+        Status::InProgress => "status-in-progress",
+        Status::Resolved => "status-resolved",
+        Status::Closed => "status-closed",
+    };
+    format!("<span class=\"status {}\">{}</span>", class, status)
+}
+
+fn get_epic_file_name(epic_id: u32) -> String {
+    format!("epic-{}.html", epic_id)
+}
+
+fn get_story_file_name(story_id: u32) -> String {
+    format!("story-{}.html", story_id)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn get_db_state() -> DBState {
+        let mut epic = Epic::new("Fruit & <veg>".to_owned(), "Buy it".to_owned());
+        epic.stories = vec![2];
+        let mut story = Story::new("Apple".to_owned(), "</script><b>".to_owned());
+        story.status = Status::InProgress;
+
+        DBState {
+            last_item_id: 2,
+            epics: HashMap::from([(1, epic)]),
+            stories: HashMap::from([(2, story)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn get_site_files_should_write_a_page_per_item() {
+        let files = get_site_files(&get_db_state()).unwrap();
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "index.html",
+                "epic-1.html",
+                "story-2.html",
+                "search-index.json"
+            ]
+        );
+
+        let index = &files[0].1;
+        assert!(index.contains("<a href=\"epic-1.html\">Fruit &amp; &lt;veg&gt;</a>"));
+        assert!(index.contains("<span class=\"status status-open\">OPEN</span>"));
+        assert!(!index.contains("</script><b>"));
+        assert!(index.contains("<\\/script><b>"));
+
+        let epic = &files[1].1;
+        assert!(epic.contains("<a href=\"story-2.html\">Apple</a>"));
+        assert!(epic.contains("<span class=\"status status-in-progress\">IN PROGRESS</span>"));
+
+        let story = &files[2].1;
+        assert!(story.contains("<a href=\"epic-1.html\">Fruit &amp; &lt;veg&gt;</a>"));
+        assert!(story.contains("&lt;/script&gt;&lt;b&gt;"));
+
+        let search_index: serde_json::Value = serde_json::from_str(&files[3].1).unwrap();
+        assert_eq!(search_index[1]["kind"], "story");
+        assert_eq!(search_index[1]["url"], "story-2.html");
+        assert_eq!(search_index[1]["status"], "IN PROGRESS");
+    }
+
+    #[test]
+    fn export_html_should_write_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site");
+
+        assert_eq!(export_html(&get_db_state(), &site).unwrap(), 4);
+        assert!(site.join("index.html").exists());
+        assert!(site.join("story-2.html").exists());
+    }
+}
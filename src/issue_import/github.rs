@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{ImportedItems, SourceEpic, SourceStory};
+use crate::models::{Epic, Status, Story};
+
+// The fields used from an issue in GitHub's issues JSON, as returned by the REST API or
+// `gh issue list --json`. Everything else ends up in other and is reported as skipped
+#[derive(Deserialize)]
+struct Issue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(default, alias = "stateReason")]
+    state_reason: Option<String>,
+    #[serde(default)]
+    milestone: Option<Milestone>,
+    // only set on pull requests, which the issues API returns alongside issues
+    #[serde(default)]
+    pull_request: Option<Value>,
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Milestone {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+// Milestones become epics and their issues become stories. Issues without a milestone are
+// imported without an epic
+pub fn parse_github_issues(json: &str) -> Result<ImportedItems> {
+    let issues: Vec<Issue> = serde_json::from_str(json)?;
+    let mut items = ImportedItems::new("GitHub");
+
+    for issue in issues {
+        let source_id = format!("issue #{}", issue.number);
+        if issue.pull_request.is_some() {
+            items
+                .skipped_items
+                .push(format!("{}: pull requests aren't imported", source_id));
+            continue;
+        }
+
+        let epic_source_id = issue.milestone.map(|milestone| {
+            let epic_source_id = format!("milestone {}", milestone.number);
+            if !items
+                .epics
+                .iter()
+                .any(|epic| epic.source_id == epic_source_id)
+            {
+                let mut epic =
+                    Epic::new(milestone.title, milestone.description.unwrap_or_default());
+                epic.status = get_status(milestone.state.as_deref().unwrap_or("open"), None);
+                items.skip_fields("milestone.", &milestone.other);
+                items.epics.push(SourceEpic {
+                    source_id: epic_source_id.clone(),
+                    epic,
+                });
+            }
+            epic_source_id
+        });
+
+        let mut story = Story::new(issue.title, issue.body.unwrap_or_default());
+        story.status = get_status(&issue.state, issue.state_reason.as_deref());
+        items.skip_fields("", &issue.other);
+        items.stories.push(SourceStory {
+            source_id,
+            epic_source_id,
+            story,
+        });
+    }
+
+    Ok(items)
+}
+
+// Closed issues are resolved unless they were closed as not planned
+fn get_status(state: &str, state_reason: Option<&str>) -> Status {
+    let state_reason = state_reason.map(str::to_lowercase);
+    match (state.to_lowercase().as_str(), state_reason.as_deref()) {
+        ("closed", Some("not_planned")) => Status::Closed,
+        ("closed", _) => Status::Resolved,
+        _ => Status::Open,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_github_issues_should_map_milestones_to_epics() {
+        let json = r#"[
+            {
+                "number": 1,
+                "title": "Login page",
+                "body": "Users can log in",
+                "state": "closed",
+                "state_reason": "completed",
+                "milestone": { "number": 4, "title": "v1.0", "description": null, "state": "open", "due_on": null },
+                "labels": [{ "name": "feature" }],
+                "assignee": null
+            },
+            {
+                "number": 2,
+                "title": "Logout",
+                "body": null,
+                "state": "CLOSED",
+                "stateReason": "NOT_PLANNED",
+                "milestone": { "number": 4, "title": "v1.0" },
+                "labels": []
+            },
+            { "number": 3, "title": "Fix typo", "state": "open", "pull_request": { "url": "" } },
+            { "number": 5, "title": "Docs", "state": "open", "milestone": null }
+        ]"#;
+
+        let items = parse_github_issues(json).unwrap();
+
+        assert_eq!(items.epics.len(), 1);
+        assert_eq!(items.epics[0].source_id, "milestone 4");
+        assert_eq!(items.epics[0].epic.name, "v1.0");
+        assert_eq!(items.epics[0].epic.status, Status::Open);
+
+        let stories: Vec<_> = items
+            .stories
+            .iter()
+            .map(|story| {
+                (
+                    story.source_id.as_str(),
+                    story.epic_source_id.as_deref(),
+                    story.story.status.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            stories,
+            vec![
+                ("issue #1", Some("milestone 4"), Status::Resolved),
+                ("issue #2", Some("milestone 4"), Status::Closed),
+                ("issue #5", None, Status::Open),
+            ]
+        );
+        assert_eq!(items.stories[0].story.description, "Users can log in");
+
+        assert_eq!(
+            items.skipped_items,
+            vec!["issue #3: pull requests aren't imported"]
+        );
+        assert_eq!(items.skipped_fields.get("labels"), Some(&2));
+        assert_eq!(items.skipped_fields.get("assignee"), None);
+    }
+
+    #[test]
+    fn parse_github_issues_should_fail_for_invalid_json() {
+        assert!(parse_github_issues("{}").is_err());
+        assert!(parse_github_issues(r#"[{ "number": 1 }]"#).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{ImportedItems, SourceEpic, SourceStory};
+use crate::models::{Epic, Status, Story};
+
+// Jira's JSON export is either the result of a search, with the issues in "issues", or a plain list
+// of issues
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Export {
+    Search { issues: Vec<Issue> },
+    List(Vec<Issue>),
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    key: String,
+    fields: Fields,
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Fields {
+    summary: String,
+    // plain text in older exports, an Atlassian document in newer ones
+    #[serde(default)]
+    description: Option<Value>,
+    issuetype: IssueType,
+    status: JiraStatus,
+    // the epic of a story in team-managed projects
+    #[serde(default)]
+    parent: Option<Parent>,
+    // the "Epic Link" field of company-managed projects
+    #[serde(default, rename = "customfield_10014")]
+    epic_link: Option<String>,
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct IssueType {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct JiraStatus {
+    name: String,
+    #[serde(default, rename = "statusCategory")]
+    category: Option<StatusCategory>,
+}
+
+#[derive(Deserialize)]
+struct StatusCategory {
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct Parent {
+    key: String,
+}
+
+// Issues of type Epic become epics, every other issue becomes a story in the epic it links to.
+// Stories linking to an epic that isn't in the file are imported without an epic
+pub fn parse_jira_issues(json: &str) -> Result<ImportedItems> {
+    let issues = match serde_json::from_str(json)? {
+        Export::Search { issues } => issues,
+        Export::List(issues) => issues,
+    };
+    let mut items = ImportedItems::new("Jira");
+
+    let epic_keys: HashSet<String> = issues
+        .iter()
+        .filter(|issue| is_epic(issue))
+        .map(|issue| issue.key.clone())
+        .collect();
+
+    for issue in issues {
+        let is_epic = is_epic(&issue);
+        let Issue { key, fields, other } = issue;
+        let description = fields
+            .description
+            .as_ref()
+            .map(get_description)
+            .unwrap_or_default();
+        let status = get_status(&fields.status);
+
+        items.skip_fields("", &other);
+        items.skip_fields("fields.", &fields.other);
+
+        if is_epic {
+            let mut epic = Epic::new(fields.summary, description);
+            epic.status = status;
+            items.epics.push(SourceEpic {
+                source_id: key,
+                epic,
+            });
+            continue;
+        }
+
+        let link = fields.epic_link.or(fields.parent.map(|parent| parent.key));
+        let epic_source_id = match link {
+            Some(link) if epic_keys.contains(&link) => Some(link),
+            Some(link) => {
+                items.skipped_items.push(format!(
+                    "{}: linked to {} which isn't an epic in the file",
+                    key, link
+                ));
+                None
+            }
+            None => None,
+        };
+
+        let mut story = Story::new(fields.summary, description);
+        story.status = status;
+        items.stories.push(SourceStory {
+            source_id: key,
+            epic_source_id,
+            story,
+        });
+    }
+
+    Ok(items)
+}
+
+fn is_epic(issue: &Issue) -> bool {
+    issue.fields.issuetype.name.eq_ignore_ascii_case("epic")
+}
+
+// Uses the status name when it matches one of ours, otherwise the category Jira puts every status
+// in: "new", "indeterminate" or "done"
+fn get_status(status: &JiraStatus) -> Status {
+    match status.name.to_lowercase().as_str() {
+        "closed" => return Status::Closed,
+        "resolved" | "done" => return Status::Resolved,
+        "in progress" => return Status::InProgress,
+        _ => {}
+    }
+    match status
+        .category
+        .as_ref()
+        .map(|category| category.key.as_str())
+    {
+        Some("done") => Status::Resolved,
+        Some("indeterminate") => Status::InProgress,
+        _ => Status::Open,
+    }
+}
+
+// The text of a plain description, or of every paragraph in an Atlassian document
+fn get_description(description: &Value) -> String {
+    match description {
+        Value::String(text) => text.clone(),
+        Value::Object(node) => {
+            if let Some(Value::String(text)) = node.get("text") {
+                return text.clone();
+            }
+            let children = match node.get("content") {
+                Some(Value::Array(children)) => children,
+                _ => return String::new(),
+            };
+            let separator = match node.get("type").and_then(Value::as_str) {
+                Some("doc") | Some("bulletList") | Some("orderedList") => "\n\n",
+                _ => "",
+            };
+            children
+                .iter()
+                .map(get_description)
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(separator)
+        }
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_jira_issues_should_map_epic_links() {
+        let json = r#"{
+            "total": 4,
+            "issues": [
+                {
+                    "id": "10001",
+                    "key": "PROJ-1",
+                    "fields": {
+                        "summary": "Checkout",
+                        "issuetype": { "name": "Epic" },
+                        "status": { "name": "In Progress", "statusCategory": { "key": "indeterminate" } },
+                        "labels": ["payments"]
+                    }
+                },
+                {
+                    "id": "10002",
+                    "key": "PROJ-2",
+                    "fields": {
+                        "summary": "Pay by card",
+                        "description": "Visa and Mastercard",
+                        "issuetype": { "name": "Story" },
+                        "status": { "name": "Done", "statusCategory": { "key": "done" } },
+                        "customfield_10014": "PROJ-1",
+                        "assignee": null
+                    }
+                },
+                {
+                    "id": "10003",
+                    "key": "PROJ-3",
+                    "fields": {
+                        "summary": "Refunds",
+                        "description": {
+                            "type": "doc",
+                            "content": [
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "Full " }, { "type": "text", "text": "refunds" }] },
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "Later" }] }
+                            ]
+                        },
+                        "issuetype": { "name": "Task" },
+                        "status": { "name": "Code Review", "statusCategory": { "key": "indeterminate" } },
+                        "parent": { "key": "PROJ-1" }
+                    }
+                },
+                {
+                    "id": "10004",
+                    "key": "PROJ-4",
+                    "fields": {
+                        "summary": "Sub-task",
+                        "issuetype": { "name": "Sub-task" },
+                        "status": { "name": "Closed" },
+                        "parent": { "key": "PROJ-3" }
+                    }
+                }
+            ]
+        }"#;
+
+        let items = parse_jira_issues(json).unwrap();
+
+        assert_eq!(items.epics.len(), 1);
+        assert_eq!(items.epics[0].source_id, "PROJ-1");
+        assert_eq!(items.epics[0].epic.status, Status::InProgress);
+
+        let stories: Vec<_> = items
+            .stories
+            .iter()
+            .map(|story| {
+                (
+                    story.source_id.as_str(),
+                    story.epic_source_id.as_deref(),
+                    story.story.status.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            stories,
+            vec![
+                ("PROJ-2", Some("PROJ-1"), Status::Resolved),
+                ("PROJ-3", Some("PROJ-1"), Status::InProgress),
+                ("PROJ-4", None, Status::Closed),
+            ]
+        );
+        assert_eq!(items.stories[0].story.description, "Visa and Mastercard");
+        assert_eq!(items.stories[1].story.description, "Full refunds\n\nLater");
+
+        assert_eq!(
+            items.skipped_items,
+            vec!["PROJ-4: linked to PROJ-3 which isn't an epic in the file"]
+        );
+        assert_eq!(items.skipped_fields.get("id"), Some(&4));
+        assert_eq!(items.skipped_fields.get("fields.labels"), Some(&1));
+        assert_eq!(items.skipped_fields.get("fields.assignee"), None);
+    }
+
+    #[test]
+    fn parse_jira_issues_should_read_a_list_of_issues() {
+        let json = r#"[{ "key": "A-1", "fields": { "summary": "One", "issuetype": { "name": "Bug" }, "status": { "name": "Open" } } }]"#;
+
+        let items = parse_jira_issues(json).unwrap();
+
+        assert_eq!(items.stories.len(), 1);
+        assert_eq!(items.stories[0].story.status, Status::Open);
+        assert!(parse_jira_issues(r#"{ "issues": [{ "key": "A-1" }] }"#).is_err());
+    }
+}
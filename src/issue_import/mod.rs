@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::models::{DBState, Epic, Story};
+
+mod github;
+mod jira;
+
+pub use github::parse_github_issues;
+pub use jira::parse_jira_issues;
+
+// An epic read from another tracker, source_id is how the other tracker refers to it
+pub struct SourceEpic {
+    pub source_id: String,
+    pub epic: Epic,
+}
+
+pub struct SourceStory {
+    pub source_id: String,
+    // source_id of the story's epic, stories without one go in an epic of their own
+    pub epic_source_id: Option<String>,
+    pub story: Story,
+}
+
+// Everything read from an export file, ready to be added to the database
+#[derive(Default)]
+pub struct ImportedItems {
+    // name of the tracker the items came from e.g. "GitHub"
+    pub source: String,
+    pub epics: Vec<SourceEpic>,
+    pub stories: Vec<SourceStory>,
+    // items that have no equivalent here, such as pull requests, and why they were skipped
+    pub skipped_items: Vec<String>,
+    // the number of items that had a value for each field that isn't imported
+    pub skipped_fields: BTreeMap<String, usize>,
+}
+
+impl ImportedItems {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    // counts every field with a value, prefix is prepended to the field names e.g. "fields."
+    fn skip_fields(&mut self, prefix: &str, fields: &HashMap<String, Value>) {
+        for (field, value) in fields {
+            if !value.is_null() {
+                *self
+                    .skipped_fields
+                    .entry(format!("{}{}", prefix, field))
+                    .or_default() += 1;
+            }
+        }
+    }
+}
+
+pub struct ImportReport {
+    pub source: String,
+    pub epics_created: usize,
+    pub stories_created: usize,
+    // (source id, new id) for every imported item e.g. ("milestone 3", "epic 12")
+    pub ids: Vec<(String, String)>,
+    pub skipped_items: Vec<String>,
+    pub skipped_fields: BTreeMap<String, usize>,
+}
+
+impl ImportReport {
+    pub fn get_text(&self) -> Result<String> {
+        let mut text = String::new();
+        writeln!(
+            text,
+            "Imported {} epic(s) and {} stories from {}",
+            self.epics_created, self.stories_created, self.source
+        )?;
+
+        text.push_str("\nIds:\n");
+        for (source_id, new_id) in &self.ids {
+            writeln!(text, "{} -> {}", source_id, new_id)?;
+        }
+
+        if !self.skipped_items.is_empty() {
+            text.push_str("\nSkipped items:\n");
+            for item in &self.skipped_items {
+                writeln!(text, "{}", item)?;
+            }
+        }
+
+        if !self.skipped_fields.is_empty() {
+            text.push_str("\nSkipped fields:\n");
+            for (field, count) in &self.skipped_fields {
+                writeln!(text, "{}: {} item(s)", field, count)?;
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+// Adds the items to db_state, taking new ids the same way as creating them by hand
+pub fn add_imported_items(db_state: &mut DBState, items: ImportedItems) -> Result<ImportReport> {
+    let mut report = ImportReport {
+        source: items.source,
+        epics_created: 0,
+        stories_created: 0,
+        ids: Vec::new(),
+        skipped_items: items.skipped_items,
+        skipped_fields: items.skipped_fields,
+    };
+
+    let mut epic_ids = HashMap::new();
+    for SourceEpic { source_id, epic } in items.epics {
+        let epic_id = db_state.add_epic(epic);
+        report
+            .ids
+            .push((source_id.clone(), format!("epic {}", epic_id)));
+        report.epics_created += 1;
+        epic_ids.insert(source_id, epic_id);
+    }
+
+    let mut unassigned_epic_id = None;
+    for SourceStory {
+        source_id,
+        epic_source_id,
+        story,
+    } in items.stories
+    {
+        let epic_id = match epic_source_id {
+            Some(epic_source_id) => *epic_ids.get(&epic_source_id).ok_or_else(|| {
+                anyhow!(
+                    "{} belongs to {} which isn't in the file",
+                    source_id,
+                    epic_source_id
+                )
+            })?,
+            None => match unassigned_epic_id {
+                Some(epic_id) => epic_id,
+                None => {
+                    let epic = Epic::new(
+                        format!("Unassigned {} issues", report.source),
+                        format!("Issues imported from {} without an epic", report.source),
+                    );
+                    let epic_id = db_state.add_epic(epic);
+                    report.epics_created += 1;
+                    unassigned_epic_id = Some(epic_id);
+                    epic_id
+                }
+            },
+        };
+
+        let story_id = db_state.add_story(story, epic_id)?;
+        report.ids.push((source_id, format!("story {}", story_id)));
+        report.stories_created += 1;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_items() -> ImportedItems {
+        let mut items = ImportedItems::new("Test");
+        items.epics.push(SourceEpic {
+            source_id: "E-1".to_owned(),
+            epic: Epic::new("Epic".to_owned(), "".to_owned()),
+        });
+        for (source_id, epic_source_id) in [("S-1", Some("E-1")), ("S-2", None), ("S-3", None)] {
+            items.stories.push(SourceStory {
+                source_id: source_id.to_owned(),
+                epic_source_id: epic_source_id.map(str::to_owned),
+                story: Story::new(source_id.to_owned(), "".to_owned()),
+            });
+        }
+        items.skip_fields(
+            "fields.",
+            &HashMap::from([
+                ("labels".to_owned(), Value::from(vec!["bug"])),
+                ("assignee".to_owned(), Value::Null),
+            ]),
+        );
+        items
+    }
+
+    #[test]
+    fn add_imported_items_should_create_epics_and_stories() {
+        let mut db_state = DBState {
+            last_item_id: 5,
+            epics: HashMap::new(),
+            stories: HashMap::new(),
+            ..Default::default()
+        };
+
+        let report = add_imported_items(&mut db_state, get_items()).unwrap();
+
+        assert_eq!(db_state.epics[&6].stories, vec![7]);
+        assert_eq!(db_state.epics[&8].name, "Unassigned Test issues");
+        assert_eq!(db_state.epics[&8].stories, vec![9, 10]);
+        assert_eq!(
+            report.get_text().unwrap(),
+            "Imported 2 epic(s) and 3 stories from Test\n\
+             \n\
+             Ids:\n\
+             E-1 -> epic 6\n\
+             S-1 -> story 7\n\
+             S-2 -> story 9\n\
+             S-3 -> story 10\n\
+             \n\
+             Skipped fields:\n\
+             fields.labels: 1 item(s)\n"
+        );
+    }
+
+    #[test]
+    fn add_imported_items_should_fail_for_missing_epics() {
+        let mut db_state = DBState::default();
+        let mut items = get_items();
+        items.stories[0].epic_source_id = Some("E-2".to_owned());
+
+        assert!(add_imported_items(&mut db_state, items).is_err());
+    }
+}
@@ -1,10 +1,30 @@
-use clearscreen;
-
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::rc::Rc;
 
 mod models;
 
+mod charts;
+mod cli;
+use cli::Command;
+
+mod csv_export;
+mod csv_import;
+mod html_export;
+mod issue_import;
+mod merge_driver;
+mod report;
+mod rpc;
+mod server;
+mod stats;
+mod time_tracking;
+
 mod db;
+mod dir_db;
+mod due_date;
+mod git_links;
+mod hooks;
 
 mod ui;
 
@@ -13,24 +33,117 @@ use io_utils::*;
 
 mod navigator;
 
+mod tui;
+use tui::RawInput;
+
 const DB_PATH: &str = r"./data/db.json";
+// when this directory exists it is used instead of DB_PATH, see dir_db
+const DB_DIR: &str = r"./data/db";
+const THEME_PATH: &str = r"./data/theme.json";
+const HOOKS_PATH: &str = r"./data/hooks.json";
+const GIT_CONFIG_PATH: &str = r"./data/git.json";
+
+// the database with the configured hooks, a broken hooks file is an error rather than running
+// without pre-hooks that might be there to stop a change
+fn open_db() -> anyhow::Result<db::JiraDatabase> {
+    let hooks = hooks::Hooks::load(HOOKS_PATH)?;
+    let db = if Path::new(DB_DIR).is_dir() {
+        db::JiraDatabase::new_directory(PathBuf::from(DB_DIR))
+    } else {
+        db::JiraDatabase::new(String::from(DB_PATH))
+    };
+    Ok(db.with_hooks(hooks))
+}
+
+// the file or directory the database is stored in
+fn get_db_path() -> &'static str {
+    if Path::new(DB_DIR).is_dir() {
+        DB_DIR
+    } else {
+        DB_PATH
+    }
+}
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let line_mode = match cli::parse_args(&args) {
+        Ok(Command::Interactive { line_mode }) => line_mode,
+        Ok(command) => {
+            // commands other than the interactive pages run once and exit
+            let result = open_db().and_then(|db| {
+                let result = cli::run_command(command, &db);
+                for message in db.take_hook_output() {
+                    eprintln!("{message}");
+                }
+                result
+            });
+            if let Err(e) = result {
+                eprintln!("ERROR: {e:#}");
+                process::exit(1);
+            }
+            return;
+        }
+        Err(e) => {
+            eprintln!("ERROR: {e}\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+
     println!("Initialising CLI Issue Tracker...");
-    let db_path = String::from(DB_PATH);
-    let db = db::JiraDatabase::new(db_path);
+    match ui::Theme::load(THEME_PATH) {
+        Ok(theme) => ui::set_theme(theme),
+        Err(e) => {
+            println!("ERROR: failed to load theme, using the default: {e}");
+            wait_for_key_press();
+        }
+    }
+    let db = match open_db() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("ERROR: {e:#}");
+            process::exit(1);
+        }
+    };
     let rc_db = Rc::new(db);
-    let mut navigator = navigator::Navigator::new(rc_db);
+    let mut navigator = navigator::Navigator::new(Rc::clone(&rc_db));
+
+    // the line-based mode can be forced with --line, e.g. for terminals that mishandle raw mode
+    let raw_mode = tui::is_supported() && !line_mode;
+    if raw_mode {
+        tui::enter().unwrap();
+    }
 
     loop {
-        clearscreen::clear().unwrap();
+        if raw_mode {
+            tui::clear_screen().unwrap();
+        } else {
+            clearscreen::clear().unwrap();
+        }
         // 1. get current page from navigator. If there is no current page exit the loop
         if let Some(page) = navigator.get_current_page() {
+            // in raw mode list pages always show a highlighted row
+            if raw_mode {
+                page.move_selection(0);
+            }
             // 2. render page
             match page.draw_page() {
                 Ok(_) => {
-                    // 3. get user input
-                    let input = get_user_input();
+                    // 3. get user input. In raw mode a single key press is enough, keys that only
+                    // move the highlight just need the page redrawn
+                    let input = if raw_mode {
+                        match tui::read_input(page) {
+                            Ok(RawInput::Command(input)) => input,
+                            Ok(RawInput::Redraw) => continue,
+                            Ok(RawInput::Quit) => break,
+                            Err(e) => {
+                                println!("ERROR: read_input() {e}");
+                                wait_for_key_press();
+                                continue;
+                            }
+                        }
+                    } else {
+                        get_user_input()
+                    };
                     // 4. pass input to page's input handler
                     match page.handle_input(input.trim()) {
                         Ok(action) => {
@@ -38,7 +151,13 @@ fn main() {
                             // process the action
                             if let Some(action) = action {
                                 if let Err(e) = navigator.handle_action(action) {
-                                    println!("ERROR: navigator.handle_action: {e}");
+                                    println!("ERROR: navigator.handle_action: {e:#}");
+                                    wait_for_key_press()
+                                }
+                                // hooks that wrote to stderr or failed after the change
+                                let hook_output = rc_db.take_hook_output();
+                                if !hook_output.is_empty() {
+                                    println!("{}", hook_output.join("\n"));
                                     wait_for_key_press()
                                 }
                             }
@@ -58,4 +177,8 @@ fn main() {
             break;
         }
     }
+
+    if raw_mode {
+        tui::leave().unwrap();
+    }
 }
@@ -0,0 +1,233 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Map, Value};
+
+use crate::dir_db::format_json;
+
+// A git merge driver for the item files of the directory database. Set it up in the repository
+// with:
+//
+//   .gitattributes:  data/db/**/*.json merge=my-jira
+//   git config merge.my-jira.driver "my-jira merge-driver %O %A %B"
+//
+// git passes the common ancestor, our version and their version, the merged file is written over
+// ours. Fields changed on one side take that side's value, lists such as an epic's stories keep
+// the additions and removals from both sides. If a field changed differently on both sides the
+// file is left to git's line merge, so ours ends up with conflict markers, and the merge fails
+pub fn merge_files(base_path: &Path, ours_path: &Path, theirs_path: &Path) -> Result<()> {
+    let base = read_value(base_path)?;
+    let ours = read_value(ours_path)?;
+    let theirs = read_value(theirs_path)?;
+
+    let mut conflicts = Vec::new();
+    let merged = merge_values(
+        base.as_ref(),
+        ours.as_ref(),
+        theirs.as_ref(),
+        "",
+        &mut conflicts,
+    )
+    .unwrap_or(Value::Null);
+
+    if !conflicts.is_empty() {
+        // same formatting on all three so only the changed lines differ
+        for (path, value) in [
+            (base_path, &base),
+            (ours_path, &ours),
+            (theirs_path, &theirs),
+        ] {
+            if let Some(value) = value {
+                fs::write(path, format_json(value)?)
+                    .with_context(|| anyhow!("failed to write {}", path.display()))?;
+            }
+        }
+        merge_lines(base_path, ours_path, theirs_path)?;
+        return Err(anyhow!(
+            "both sides changed {} in {}",
+            conflicts.join(", "),
+            ours_path.display()
+        ));
+    }
+
+    fs::write(ours_path, format_json(&merged)?)
+        .with_context(|| anyhow!("failed to write {}", ours_path.display()))
+}
+
+// git merge-file exits with the number of conflicts, or above 127 if it failed
+fn merge_lines(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let status = Command::new("git")
+        .args(["merge-file", "-L", "ours", "-L", "base", "-L", "theirs"])
+        .args([ours, base, theirs])
+        .status()
+        .context("failed to run git")?;
+    match status.code() {
+        Some(0..=127) => Ok(()),
+        _ => Err(anyhow!("git merge-file failed on {}", ours.display())),
+    }
+}
+
+// an empty file, which git passes as the ancestor when both sides added the file, is None
+fn read_value(path: &Path) -> Result<Option<Value>> {
+    let content =
+        fs::read_to_string(path).with_context(|| anyhow!("failed to read {}", path.display()))?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&content)
+        .map(Some)
+        .with_context(|| anyhow!("failed to parse {}", path.display()))
+}
+
+// Three-way merge of one value, None is a missing field. path names the field in conflicts
+fn merge_values(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    match (base, ours, theirs) {
+        (base, Some(Value::Object(ours)), Some(Value::Object(theirs))) => {
+            let empty = Map::new();
+            let base = match base {
+                Some(Value::Object(base)) => base,
+                _ => &empty,
+            };
+            let mut keys: Vec<&String> = base
+                .keys()
+                .chain(ours.keys())
+                .chain(theirs.keys())
+                .collect();
+            keys.sort();
+            keys.dedup();
+
+            let mut merged = Map::new();
+            for key in keys {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                if let Some(value) = merge_values(
+                    base.get(key),
+                    ours.get(key),
+                    theirs.get(key),
+                    &field,
+                    conflicts,
+                ) {
+                    merged.insert(key.clone(), value);
+                }
+            }
+            Some(Value::Object(merged))
+        }
+        (base, Some(Value::Array(ours)), Some(Value::Array(theirs))) => {
+            let empty = Vec::new();
+            let base = match base {
+                Some(Value::Array(base)) => base,
+                _ => &empty,
+            };
+            // keep ours except what they removed, then add what they added
+            let mut merged: Vec<Value> = ours
+                .iter()
+                .filter(|value| theirs.contains(value) || !base.contains(value))
+                .cloned()
+                .collect();
+            for value in theirs {
+                if !base.contains(value) && !merged.contains(value) {
+                    merged.push(value.clone());
+                }
+            }
+            Some(Value::Array(merged))
+        }
+        _ => {
+            conflicts.push(if path.is_empty() {
+                "the whole file".to_owned()
+            } else {
+                path.to_owned()
+            });
+            ours.cloned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn merge(base: Value, ours: Value, theirs: Value) -> (Value, Vec<String>) {
+        let mut conflicts = Vec::new();
+        let merged = merge_values(Some(&base), Some(&ours), Some(&theirs), "", &mut conflicts);
+        (merged.unwrap(), conflicts)
+    }
+
+    #[test]
+    fn merge_values_should_combine_changes_to_different_fields() {
+        let base = json!({ "name": "Epic", "status": "Open", "stories": [2, 3] });
+        let ours = json!({ "name": "Renamed", "status": "Open", "stories": [2, 3, 4] });
+        let theirs = json!({ "name": "Epic", "status": "Closed", "stories": [3, 5], "new": 1 });
+
+        assert_eq!(
+            merge(base, ours, theirs),
+            (
+                json!({ "name": "Renamed", "status": "Closed", "stories": [3, 4, 5], "new": 1 }),
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn merge_values_should_report_conflicts() {
+        let base = json!({ "name": "Epic", "status": "Open" });
+        let ours = json!({ "name": "Ours", "status": "Resolved" });
+        let theirs = json!({ "name": "Theirs", "status": "Resolved" });
+
+        assert_eq!(
+            merge(base, ours, theirs),
+            (
+                json!({ "name": "Ours", "status": "Resolved" }),
+                vec!["name".to_owned()]
+            )
+        );
+    }
+
+    #[test]
+    fn merge_files_should_write_the_result_over_ours() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let base = write("base", r#"{ "name": "a", "description": "" }"#);
+        let ours = write("ours", r#"{ "name": "b", "description": "" }"#);
+        let theirs = write("theirs", r#"{ "name": "a", "description": "c" }"#);
+
+        merge_files(&base, &ours, &theirs).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&ours).unwrap(),
+            "{\n  \"description\": \"c\",\n  \"name\": \"b\"\n}\n"
+        );
+
+        let empty = write("empty", "");
+        let theirs = write("theirs", r#"{ "name": "d", "description": "c" }"#);
+        let error = merge_files(&empty, &ours, &theirs).unwrap_err();
+        assert!(error.to_string().starts_with("both sides changed name in "));
+        assert_eq!(
+            fs::read_to_string(&ours).unwrap(),
+            "{\n  \"description\": \"c\",\n<<<<<<< ours\n  \"name\": \"b\"\n=======\n  \"name\": \"d\"\n>>>>>>> theirs\n}\n"
+        );
+    }
+}
@@ -1,22 +1,69 @@
+use chrono::{DateTime, Local, NaiveDate};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+
+use crate::due_date::today;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Action {
     NavigateToEpicDetail { epic_id: u32 },
     NavigateToStoryDetail { epic_id: u32, story_id: u32 },
     NavigateToPreviousPage,
+    NavigateToBoard { epic_id: Option<u32> },
+    NavigateToOverdue,
+    NavigateToSprints,
+    NavigateToSprintDetail { sprint_id: u32 },
+    NavigateToChart { scope: ChartScope },
+    NavigateToStats,
+    // None shows the epics of every project
+    NavigateToProject { project_id: Option<u32> },
+    CreateProject,
     CreateEpic,
+    CreateEpicInEditor,
+    EditEpic { epic_id: u32 },
     UpdateEpicStatus { epic_id: u32 },
+    UpdateEpicDueDate { epic_id: u32 },
     DeleteEpic { epic_id: u32 },
+    MoveEpic { epic_id: u32 },
     CreateStory { epic_id: u32 },
+    CreateStoryInEditor { epic_id: u32 },
+    EditStory { story_id: u32 },
     UpdateStoryStatus { story_id: u32 },
+    UpdateStoryDueDate { story_id: u32 },
+    MoveStory { story_id: u32, status: Status },
+    CycleSortKey { page: ListPage },
+    ToggleSortDirection { page: ListPage },
     DeleteStory { epic_id: u32, story_id: u32 },
+    CreateSprint,
+    AddStoryToSprint { sprint_id: u32 },
+    RemoveStoryFromSprint { sprint_id: u32 },
+    CloseSprint { sprint_id: u32 },
+    StartTimer { story_id: u32 },
+    StopTimer { story_id: u32 },
+    LogWork { story_id: u32 },
+    AddStoryLink { story_id: u32 },
+    RemoveStoryLink { story_id: u32 },
     Exit,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+// The stories a chart counts
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ChartScope {
+    #[default]
+    All,
+    Epic(u32),
+    Sprint(u32),
+}
+
+// declaration order is the workflow order, which is used when sorting by status
+#[derive(
+    Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone,
+)]
 pub enum Status {
     Open,
     InProgress,
@@ -24,6 +71,21 @@ pub enum Status {
     Closed,
 }
 
+impl Status {
+    // every status in workflow order, used to lay out the columns of the board
+    pub const ALL: [Status; 4] = [
+        Status::Open,
+        Status::InProgress,
+        Status::Resolved,
+        Status::Closed,
+    ];
+
+    // resolved and closed items need no more work
+    pub fn is_done(&self) -> bool {
+        matches!(self, Status::Resolved | Status::Closed)
+    }
+}
+
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -35,12 +97,53 @@ impl Display for Status {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+// accepts the numbers used by the update status prompt as well as the displayed names, ignoring
+// case and separators so "in progress", "in-progress" and "inprogress" are all valid
+impl FromStr for Status {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalised: String = s
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        match normalised.as_str() {
+            "1" | "open" => Ok(Status::Open),
+            "2" | "inprogress" => Ok(Status::InProgress),
+            "3" | "resolved" => Ok(Status::Resolved),
+            "4" | "closed" => Ok(Status::Closed),
+            _ => Err(anyhow!("invalid status: {}", s)),
+        }
+    }
+}
+
+// A team's epics, kept apart from other projects. The key is a short code such as "WEB"
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    pub key: String,
+    pub epics: Vec<u32>,
+}
+
+impl Project {
+    pub fn new(name: String, key: String) -> Self {
+        Self {
+            name,
+            key,
+            epics: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
     pub stories: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
 }
 
 impl Epic {
@@ -50,15 +153,21 @@ impl Epic {
             description,
             status: Status::Open,
             stories: vec![],
+            due_date: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
+    // git commits that mention the story, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<Commit>,
 }
 
 impl Story {
@@ -67,13 +176,1225 @@ impl Story {
             name,
             description,
             status: Status::Open,
+            due_date: None,
+            commits: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    // author date in ISO 8601 format as git prints it
+    pub date: String,
+    pub subject: String,
+    // a "fixes #id" commit whose story link-commits hasn't resolved yet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resolve_pending: bool,
+}
+
+// A time box of stories from any epic, usually two weeks
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct Sprint {
+    pub name: String,
+    pub goal: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub stories: Vec<u32>,
+    // closed sprints keep their stories as a record of what was planned
+    #[serde(default)]
+    pub closed: bool,
+    // None when the sprint was planned outside any project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u32>,
+}
+
+impl Sprint {
+    pub fn new(name: String, goal: String, start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            name,
+            goal,
+            start_date,
+            end_date,
+            stories: vec![],
+            closed: false,
+            project_id: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+// An entry in the log of story status changes, which the burndown charts are drawn from
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct StatusChange {
+    pub story_id: u32,
+    pub date: NaiveDate,
+    // None when the story was created with this status
+    pub from: Option<Status>,
+    pub to: Status,
+}
+
+// Time spent on a story, entered by hand or recorded by stopping a timer
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct Worklog {
+    pub story_id: u32,
+    pub user: String,
+    pub date: NaiveDate,
+    pub minutes: u32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    // the story's name once it has been deleted, logged time is kept for timesheets
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub story_name: String,
+}
+
+// A timer started on a story, stored in the database so it keeps running when the app is closed
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct Timer {
+    pub story_id: u32,
+    pub user: String,
+    pub started_at: DateTime<Local>,
+}
+
+// How one story is linked to another, from the first story's point of view
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum LinkKind {
+    Blocks,
+    BlockedBy,
+    RelatesTo,
+}
+
+impl LinkKind {
+    // the same link seen from the other story
+    pub fn inverse(&self) -> Self {
+        match self {
+            LinkKind::Blocks => LinkKind::BlockedBy,
+            LinkKind::BlockedBy => LinkKind::Blocks,
+            LinkKind::RelatesTo => LinkKind::RelatesTo,
+        }
+    }
+}
+
+impl Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkKind::Blocks => write!(f, "blocks"),
+            LinkKind::BlockedBy => write!(f, "blocked by"),
+            LinkKind::RelatesTo => write!(f, "relates to"),
+        }
+    }
+}
+
+// A link between two stories. Only Blocks and RelatesTo are stored, "a blocked by b" is kept as
+// "b blocks a"
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct StoryLink {
+    pub from: u32,
+    pub to: u32,
+    pub kind: LinkKind,
+}
+
+impl StoryLink {
+    // the kind of link and the other story, seen from story_id, None if it isn't linked
+    pub fn get_other(&self, story_id: u32) -> Option<(LinkKind, u32)> {
+        if self.from == story_id {
+            Some((self.kind, self.to))
+        } else if self.to == story_id {
+            Some((self.kind.inverse(), self.from))
+        } else {
+            None
+        }
+    }
+}
+
+// implemented by the items shown in list pages so they can share one sort implementation
+pub trait ListItem {
+    fn name(&self) -> &str;
+    fn status(&self) -> &Status;
+    fn due_date(&self) -> Option<NaiveDate>;
+
+    // past its due date and not finished
+    fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.due_date().is_some_and(|due_date| due_date < today) && !self.status().is_done()
+    }
+}
+
+impl ListItem for Epic {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn due_date(&self) -> Option<NaiveDate> {
+        self.due_date
+    }
+}
+
+impl ListItem for Story {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn due_date(&self) -> Option<NaiveDate> {
+        self.due_date
+    }
+}
+
+// the list pages whose sort order is remembered
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum ListPage {
+    Home,
+    EpicDetail,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum SortKey {
+    #[default]
+    Id,
+    Name,
+    Status,
+}
+
+impl SortKey {
+    pub fn next(&self) -> Self {
+        match self {
+            SortKey::Id => SortKey::Name,
+            SortKey::Name => SortKey::Status,
+            SortKey::Status => SortKey::Id,
+        }
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortKey::Id => write!(f, "id"),
+            SortKey::Name => write!(f, "name"),
+            SortKey::Status => write!(f, "status"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn reversed(&self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub direction: SortDirection,
+}
+
+impl SortOrder {
+    // sorts (id, item) pairs in place. Ties are broken by id so the order is always stable
+    pub fn sort<T: ListItem>(&self, items: &mut [(u32, &T)]) {
+        items.sort_by(|(a_id, a), (b_id, b)| {
+            let ordering = match self.key {
+                SortKey::Id => a_id.cmp(b_id),
+                SortKey::Name => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+                SortKey::Status => a.status().cmp(b.status()),
+            }
+            .then_with(|| a_id.cmp(b_id));
+
+            match self.direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+}
+
+// An id that doesn't match an item. Kept as its own type so callers, such as the server, can tell
+// a bad id apart from other failures with downcast_ref()
+#[derive(Debug, PartialEq, Eq)]
+pub struct NotFoundError(pub &'static str);
+
+impl Display for NotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for NotFoundError {}
+
+// Moving a story to IN PROGRESS while stories blocking it are unfinished. Callers can warn about
+// the blockers and go ahead anyway with JiraDatabase::force_story_status()
+#[derive(Debug, PartialEq, Eq)]
+pub struct BlockedError {
+    pub story_id: u32,
+    pub blockers: Vec<u32>,
+}
+
+impl Display for BlockedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let blockers: Vec<String> = self.blockers.iter().map(u32::to_string).collect();
+        write!(
+            f,
+            "story {} is blocked by {}",
+            self.story_id,
+            blockers.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for BlockedError {}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone, Default)]
 pub struct DBState {
     pub last_item_id: u32,
     pub epics: HashMap<u32, Epic>,
     pub stories: HashMap<u32, Story>,
+    // missing from databases created before sorting was added
+    #[serde(default)]
+    pub sort_orders: HashMap<ListPage, SortOrder>,
+    #[serde(default)]
+    pub sprints: HashMap<u32, Sprint>,
+    // oldest first, only kept since the log was added so older stories have no entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status_changes: Vec<StatusChange>,
+    // oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worklogs: Vec<Worklog>,
+    // running timers, each user has at most one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timers: Vec<Timer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub story_links: Vec<StoryLink>,
+    // epics that aren't in any project, such as those created before projects were added, are
+    // only listed when every project is shown
+    #[serde(default)]
+    pub projects: HashMap<u32, Project>,
+}
+
+impl DBState {
+    // every new project, epic, story and sprint takes the next id, they all share the same sequence.
+    // Ids that are taken are skipped, the directory database counts up from a random id that can
+    // run into existing items
+    pub fn next_item_id(&mut self) -> u32 {
+        self.last_item_id += 1;
+        while self.is_item_id_used(self.last_item_id) {
+            self.last_item_id += 1;
+        }
+        self.last_item_id
+    }
+
+    // the time logged on a deleted story keeps its id
+    pub fn is_item_id_used(&self, id: u32) -> bool {
+        self.epics.contains_key(&id)
+            || self.stories.contains_key(&id)
+            || self.sprints.contains_key(&id)
+            || self.projects.contains_key(&id)
+            || self.worklogs.iter().any(|worklog| worklog.story_id == id)
+    }
+
+    pub fn add_epic(&mut self, epic: Epic) -> u32 {
+        let new_id = self.next_item_id();
+        self.epics.insert(new_id, epic);
+        new_id
+    }
+
+    // Keys are letters and digits starting with a letter, upper-cased and unique
+    pub fn add_project(&mut self, mut project: Project) -> Result<u32, Error> {
+        project.key = project.key.trim().to_uppercase();
+        let valid = project
+            .key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+            && project.key.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid {
+            return Err(anyhow!(
+                "invalid project key: {} (use letters and digits)",
+                project.key
+            ));
+        }
+        if self.projects.values().any(|other| other.key == project.key) {
+            return Err(anyhow!("project key {} is already used", project.key));
+        }
+
+        let new_id = self.next_item_id();
+        self.projects.insert(new_id, project);
+        Ok(new_id)
+    }
+
+    // Moves the epic out of whichever project it is in. Its stories are listed on the epic, so
+    // they go with it
+    pub fn move_epic(&mut self, epic_id: u32, project_id: u32) -> Result<(), Error> {
+        if !self.epics.contains_key(&epic_id) {
+            return Err(anyhow!(NotFoundError("could not find epic in database!")));
+        }
+        if !self.projects.contains_key(&project_id) {
+            return Err(anyhow!(NotFoundError(
+                "could not find project in database!"
+            )));
+        }
+
+        self.remove_epic_references(epic_id);
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.epics.push(epic_id);
+        }
+        Ok(())
+    }
+
+    // the project the epic is in, None if it isn't in one
+    pub fn get_project_id(&self, epic_id: u32) -> Option<u32> {
+        self.projects
+            .iter()
+            .find(|(_, project)| project.epics.contains(&epic_id))
+            .map(|(id, _)| *id)
+    }
+
+    // the key is matched ignoring case, as it is stored in upper case
+    pub fn get_project_by_key(&self, key: &str) -> Option<u32> {
+        self.projects
+            .iter()
+            .find(|(_, project)| project.key.eq_ignore_ascii_case(key.trim()))
+            .map(|(id, _)| *id)
+    }
+
+    // every project by key
+    pub fn get_sorted_projects(&self) -> Vec<(u32, &Project)> {
+        let mut projects: Vec<_> = self
+            .projects
+            .iter()
+            .map(|(id, project)| (*id, project))
+            .collect();
+        projects.sort_by(|(_, a), (_, b)| a.key.cmp(&b.key));
+        projects
+    }
+
+    // the stories in the project's epics
+    pub fn get_project_story_ids(&self, project_id: u32) -> Result<HashSet<u32>, Error> {
+        let project = self
+            .projects
+            .get(&project_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find project in database!")))?;
+        Ok(project
+            .epics
+            .iter()
+            .filter_map(|id| self.epics.get(id))
+            .flat_map(|epic| epic.stories.iter().copied())
+            .collect())
+    }
+
+    // A copy with only the project's epics, stories and sprints. Sprints keep only the project's
+    // stories and the history, time and links of other stories are dropped, so nothing outside
+    // the project can be reached from it
+    pub fn get_project_view(mut self, project_id: u32) -> Result<DBState, Error> {
+        let story_ids = self.get_project_story_ids(project_id)?;
+        let epic_ids = self.projects[&project_id].epics.clone();
+        self.epics.retain(|id, _| epic_ids.contains(id));
+        self.stories.retain(|id, _| story_ids.contains(id));
+        self.sprints
+            .retain(|_, sprint| sprint.project_id == Some(project_id));
+        for sprint in self.sprints.values_mut() {
+            sprint.stories.retain(|id| story_ids.contains(id));
+        }
+        self.status_changes
+            .retain(|change| story_ids.contains(&change.story_id));
+        self.worklogs
+            .retain(|worklog| story_ids.contains(&worklog.story_id));
+        self.timers
+            .retain(|timer| story_ids.contains(&timer.story_id));
+        self.story_links
+            .retain(|link| story_ids.contains(&link.from) && story_ids.contains(&link.to));
+        Ok(self)
+    }
+
+    // called when an epic is deleted or moved so no project lists it
+    pub fn remove_epic_references(&mut self, epic_id: u32) {
+        for project in self.projects.values_mut() {
+            project.epics.retain(|id| *id != epic_id);
+        }
+    }
+
+    pub fn add_story(&mut self, story: Story, epic_id: u32) -> Result<u32, Error> {
+        if !self.epics.contains_key(&epic_id) {
+            return Err(anyhow!(NotFoundError("could not find epic in database!")));
+        }
+
+        let new_id = self.next_item_id();
+        // a change from no status records when the story was created
+        self.status_changes.push(StatusChange {
+            story_id: new_id,
+            date: today(),
+            from: None,
+            to: story.status.clone(),
+        });
+        self.stories.insert(new_id, story);
+        if let Some(epic) = self.epics.get_mut(&epic_id) {
+            epic.stories.push(new_id);
+        }
+        Ok(new_id)
+    }
+
+    pub fn add_sprint(&mut self, sprint: Sprint) -> u32 {
+        let new_id = self.next_item_id();
+        self.sprints.insert(new_id, sprint);
+        new_id
+    }
+
+    // A story can only be planned in one open sprint at a time
+    pub fn add_story_to_sprint(&mut self, sprint_id: u32, story_id: u32) -> Result<(), Error> {
+        if !self.stories.contains_key(&story_id) {
+            return Err(anyhow!(NotFoundError("could not find story in database!")));
+        }
+        if let Some((other_id, _)) = self.sprints.iter().find(|(id, sprint)| {
+            **id != sprint_id && !sprint.closed && sprint.stories.contains(&story_id)
+        }) {
+            return Err(anyhow!(
+                "story {} is already in sprint {}",
+                story_id,
+                other_id
+            ));
+        }
+
+        let sprint = self.get_open_sprint_mut(sprint_id)?;
+        if !sprint.stories.contains(&story_id) {
+            sprint.stories.push(story_id);
+        }
+        Ok(())
+    }
+
+    pub fn remove_story_from_sprint(&mut self, sprint_id: u32, story_id: u32) -> Result<(), Error> {
+        let sprint = self.get_open_sprint_mut(sprint_id)?;
+        let story_index = sprint
+            .stories
+            .iter()
+            .position(|id| id == &story_id)
+            .ok_or_else(|| anyhow!(NotFoundError("story id not found in sprint stories vector")))?;
+        sprint.stories.remove(story_index);
+        Ok(())
+    }
+
+    // Closes the sprint, moving its unfinished stories into the sprint carry_over_to if given.
+    // Returns how many stories were carried over
+    pub fn close_sprint(
+        &mut self,
+        sprint_id: u32,
+        carry_over_to: Option<u32>,
+    ) -> Result<usize, Error> {
+        let sprint = self.get_open_sprint_mut(sprint_id)?;
+        sprint.closed = true;
+        let Some(next_id) = carry_over_to else {
+            return Ok(0);
+        };
+        if next_id == sprint_id {
+            return Err(anyhow!("cannot carry stories over into the same sprint"));
+        }
+
+        let unfinished = self.get_unfinished_stories(sprint_id);
+        for story_id in &unfinished {
+            self.add_story_to_sprint(next_id, *story_id)?;
+        }
+        Ok(unfinished.len())
+    }
+
+    // the stories of a sprint that aren't RESOLVED or CLOSED
+    pub fn get_unfinished_stories(&self, sprint_id: u32) -> Vec<u32> {
+        self.sprints.get(&sprint_id).map_or(vec![], |sprint| {
+            sprint
+                .stories
+                .iter()
+                .filter(|id| {
+                    self.stories
+                        .get(id)
+                        .is_some_and(|story| !story.status.is_done())
+                })
+                .copied()
+                .collect()
+        })
+    }
+
+    // the open sprint starting soonest, other than sprint_id, which unfinished stories are
+    // offered to when sprint_id is closed
+    pub fn get_next_sprint(&self, sprint_id: u32) -> Option<u32> {
+        self.sprints
+            .iter()
+            .filter(|(id, sprint)| **id != sprint_id && !sprint.closed)
+            .min_by_key(|(id, sprint)| (sprint.start_date, **id))
+            .map(|(id, _)| *id)
+    }
+
+    // every sprint, the latest first
+    pub fn get_sorted_sprints(&self) -> Vec<(u32, &Sprint)> {
+        let mut sprints: Vec<_> = self
+            .sprints
+            .iter()
+            .map(|(id, sprint)| (*id, sprint))
+            .collect();
+        sprints.sort_by_key(|(id, sprint)| std::cmp::Reverse((sprint.start_date, *id)));
+        sprints
+    }
+
+    // the epic a story belongs to
+    pub fn get_epic_id(&self, story_id: u32) -> Option<u32> {
+        self.epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(id, _)| *id)
+    }
+
+    // Removes a story along with its place in sprints and its history, its epic is left to the
+    // caller. Logged time is kept under the story's name, a running timer has to be stopped first
+    pub fn remove_story(&mut self, story_id: u32) -> Result<(), Error> {
+        if let Some(timer) = self.timers.iter().find(|timer| timer.story_id == story_id) {
+            return Err(anyhow!(
+                "{} has a timer running on story {}, stop it first",
+                timer.user,
+                story_id
+            ));
+        }
+        let story = self
+            .stories
+            .remove(&story_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find story in database!")))?;
+
+        for sprint in self.sprints.values_mut() {
+            sprint.stories.retain(|id| *id != story_id);
+        }
+        self.status_changes
+            .retain(|change| change.story_id != story_id);
+        for worklog in &mut self.worklogs {
+            if worklog.story_id == story_id {
+                worklog.story_name = story.name.clone();
+            }
+        }
+        self.story_links
+            .retain(|link| link.get_other(story_id).is_none());
+        Ok(())
+    }
+
+    // Links two stories. A story can't be linked to itself or linked twice to the same story, and
+    // blocking links can't form a cycle, which would leave every story in it blocked forever
+    pub fn add_story_link(
+        &mut self,
+        story_id: u32,
+        kind: LinkKind,
+        other_id: u32,
+    ) -> Result<(), Error> {
+        if !self.stories.contains_key(&story_id) || !self.stories.contains_key(&other_id) {
+            return Err(anyhow!(NotFoundError("could not find story in database!")));
+        }
+        if story_id == other_id {
+            return Err(anyhow!("a story can't be linked to itself"));
+        }
+        if let Some((linked_kind, _)) = self
+            .get_story_links(story_id)
+            .into_iter()
+            .find(|(_, id)| *id == other_id)
+        {
+            return Err(anyhow!(
+                "story {} already {} story {}",
+                story_id,
+                linked_kind,
+                other_id
+            ));
+        }
+
+        let link = match kind {
+            LinkKind::BlockedBy => StoryLink {
+                from: other_id,
+                to: story_id,
+                kind: LinkKind::Blocks,
+            },
+            kind => StoryLink {
+                from: story_id,
+                to: other_id,
+                kind,
+            },
+        };
+        if link.kind == LinkKind::Blocks {
+            if let Some(path) = self.get_blocking_path(link.to, link.from) {
+                let path: Vec<String> = [link.from]
+                    .iter()
+                    .chain(&path)
+                    .map(|id| id.to_string())
+                    .collect();
+                return Err(anyhow!(
+                    "link would create a cycle: {}",
+                    path.join(" blocks ")
+                ));
+            }
+        }
+        self.story_links.push(link);
+        Ok(())
+    }
+
+    // removes every link between the two stories
+    pub fn remove_story_link(&mut self, story_id: u32, other_id: u32) -> Result<(), Error> {
+        let links = self.story_links.len();
+        self.story_links.retain(|link| {
+            link.get_other(story_id)
+                .is_none_or(|(_, id)| id != other_id)
+        });
+        if self.story_links.len() == links {
+            return Err(anyhow!(NotFoundError("the stories aren't linked")));
+        }
+        Ok(())
+    }
+
+    // every link of the story from its point of view, in the order they were added
+    pub fn get_story_links(&self, story_id: u32) -> Vec<(LinkKind, u32)> {
+        self.story_links
+            .iter()
+            .filter_map(|link| link.get_other(story_id))
+            .collect()
+    }
+
+    // the stories blocking this one that aren't RESOLVED or CLOSED yet
+    pub fn get_open_blockers(&self, story_id: u32) -> Vec<u32> {
+        self.get_story_links(story_id)
+            .into_iter()
+            .filter(|(kind, id)| {
+                *kind == LinkKind::BlockedBy
+                    && self
+                        .stories
+                        .get(id)
+                        .is_some_and(|story| !story.status.is_done())
+            })
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    // Errors with BlockedError if the status would start work on a story that is waiting on
+    // unfinished stories
+    pub fn check_can_start(&self, story_id: u32, status: &Status) -> Result<(), Error> {
+        if *status != Status::InProgress
+            || self
+                .stories
+                .get(&story_id)
+                .is_none_or(|story| story.status == Status::InProgress)
+        {
+            return Ok(());
+        }
+        let blockers = self.get_open_blockers(story_id);
+        if blockers.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(BlockedError { story_id, blockers }))
+    }
+
+    // unfinished and waiting on an unfinished story
+    pub fn is_blocked(&self, story_id: u32) -> bool {
+        self.stories
+            .get(&story_id)
+            .is_some_and(|story| !story.status.is_done())
+            && !self.get_open_blockers(story_id).is_empty()
+    }
+
+    // The stories from start to end if start blocks end directly or through other stories
+    fn get_blocking_path(&self, start: u32, end: u32) -> Option<Vec<u32>> {
+        let mut path = vec![start];
+        let mut visited = vec![start];
+        // depth first, the last id of path is the story being searched from
+        while let Some(current) = path.last().copied() {
+            if current == end {
+                return Some(path);
+            }
+            let next = self.story_links.iter().find(|link| {
+                link.kind == LinkKind::Blocks && link.from == current && !visited.contains(&link.to)
+            });
+            match next {
+                Some(link) => {
+                    visited.push(link.to);
+                    path.push(link.to);
+                }
+                None => {
+                    path.pop();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn add_worklog(&mut self, worklog: Worklog) -> Result<(), Error> {
+        if !self.stories.contains_key(&worklog.story_id) {
+            return Err(anyhow!(NotFoundError("could not find story in database!")));
+        }
+        if worklog.minutes == 0 {
+            return Err(anyhow!("cannot log no time"));
+        }
+        self.worklogs.push(worklog);
+        Ok(())
+    }
+
+    // A user can only time one story at a time
+    pub fn start_timer(
+        &mut self,
+        story_id: u32,
+        user: &str,
+        now: DateTime<Local>,
+    ) -> Result<(), Error> {
+        if !self.stories.contains_key(&story_id) {
+            return Err(anyhow!(NotFoundError("could not find story in database!")));
+        }
+        if let Some(timer) = self.timers.iter().find(|timer| timer.user == user) {
+            return Err(anyhow!(
+                "a timer is already running on story {}",
+                timer.story_id
+            ));
+        }
+        self.timers.push(Timer {
+            story_id,
+            user: user.to_owned(),
+            started_at: now,
+        });
+        Ok(())
+    }
+
+    // Stops the user's timer on the story and logs the time on the day it was stopped, rounded to
+    // the nearest minute but at least one
+    pub fn stop_timer(
+        &mut self,
+        story_id: u32,
+        user: &str,
+        now: DateTime<Local>,
+    ) -> Result<Worklog, Error> {
+        let timer_index = self
+            .timers
+            .iter()
+            .position(|timer| timer.story_id == story_id && timer.user == user)
+            .ok_or_else(|| anyhow!(NotFoundError("no timer is running on this story")))?;
+        let timer = self.timers.remove(timer_index);
+        let seconds = (now - timer.started_at).num_seconds().max(0);
+        let worklog = Worklog {
+            story_id,
+            user: timer.user,
+            date: now.date_naive(),
+            minutes: u32::try_from((seconds + 30) / 60)
+                .unwrap_or(u32::MAX)
+                .max(1),
+            note: String::new(),
+            story_name: String::new(),
+        };
+        self.add_worklog(worklog.clone())?;
+        Ok(worklog)
+    }
+
+    pub fn get_timer(&self, story_id: u32, user: &str) -> Option<&Timer> {
+        self.timers
+            .iter()
+            .find(|timer| timer.story_id == story_id && timer.user == user)
+    }
+
+    // total time logged on the story, by every user
+    pub fn get_story_minutes(&self, story_id: u32) -> u32 {
+        self.worklogs
+            .iter()
+            .filter(|worklog| worklog.story_id == story_id)
+            .map(|worklog| worklog.minutes)
+            .sum()
+    }
+
+    // total time logged on the stories of the epic
+    pub fn get_epic_minutes(&self, epic_id: u32) -> u32 {
+        self.epics.get(&epic_id).map_or(0, |epic| {
+            epic.stories
+                .iter()
+                .map(|story_id| self.get_story_minutes(*story_id))
+                .sum()
+        })
+    }
+
+    // sets the story's status, logging the change if it is a different status
+    pub fn set_story_status(
+        &mut self,
+        story_id: u32,
+        status: Status,
+        date: NaiveDate,
+    ) -> Result<(), Error> {
+        let story = self
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find story in database!")))?;
+        if story.status == status {
+            return Ok(());
+        }
+        self.status_changes.push(StatusChange {
+            story_id,
+            date,
+            from: Some(story.status.clone()),
+            to: status.clone(),
+        });
+        story.status = status;
+        Ok(())
+    }
+
+    fn get_open_sprint_mut(&mut self, sprint_id: u32) -> Result<&mut Sprint, Error> {
+        let sprint = self
+            .sprints
+            .get_mut(&sprint_id)
+            .ok_or_else(|| anyhow!(NotFoundError("could not find sprint in database!")))?;
+        if sprint.closed {
+            return Err(anyhow!("sprint {} is closed", sprint_id));
+        }
+        Ok(sprint)
+    }
+
+    pub fn get_sort_order(&self, page: ListPage) -> SortOrder {
+        self.sort_orders.get(&page).copied().unwrap_or_default()
+    }
+
+    // every epic in the order the HomePage lists them
+    pub fn get_sorted_epics(&self) -> Vec<(u32, &Epic)> {
+        let mut epics: Vec<_> = self.epics.iter().map(|(id, epic)| (*id, epic)).collect();
+        self.get_sort_order(ListPage::Home).sort(&mut epics);
+        epics
+    }
+
+    // the stories of an epic in the order the EpicDetail page lists them. Ids with no matching
+    // story are skipped
+    pub fn get_sorted_stories(&self, epic: &Epic) -> Vec<(u32, &Story)> {
+        let mut stories: Vec<_> = epic
+            .stories
+            .iter()
+            .filter_map(|id| self.stories.get(id).map(|story| (*id, story)))
+            .collect();
+        self.get_sort_order(ListPage::EpicDetail).sort(&mut stories);
+        stories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_order_should_sort_items() {
+        let mut apple = Story::new("apple".to_owned(), "".to_owned());
+        apple.status = Status::Closed;
+        let banana = Story::new("Banana".to_owned(), "".to_owned());
+        let mut cherry = Story::new("cherry".to_owned(), "".to_owned());
+        cherry.status = Status::InProgress;
+
+        let mut items = vec![(3, &banana), (1, &cherry), (2, &apple)];
+        let ids = |items: &[(u32, &Story)]| items.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let mut sort_order = SortOrder::default();
+        sort_order.sort(&mut items);
+        assert_eq!(ids(&items), vec![1, 2, 3]);
+
+        sort_order.key = SortKey::Name;
+        sort_order.sort(&mut items);
+        assert_eq!(ids(&items), vec![2, 3, 1]);
+
+        sort_order.key = SortKey::Status;
+        sort_order.sort(&mut items);
+        assert_eq!(ids(&items), vec![3, 1, 2]);
+
+        sort_order.direction = SortDirection::Descending;
+        sort_order.sort(&mut items);
+        assert_eq!(ids(&items), vec![2, 1, 3]);
+    }
+
+    fn get_sprint_state() -> (DBState, u32, u32, Vec<u32>) {
+        let mut db_state = DBState::default();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        let story_ids: Vec<u32> = [Status::Open, Status::Resolved, Status::InProgress]
+            .into_iter()
+            .map(|status| {
+                let mut story = Story::new("".to_owned(), "".to_owned());
+                story.status = status;
+                db_state.add_story(story, epic_id).unwrap()
+            })
+            .collect();
+        let first =
+            db_state.add_sprint(Sprint::new("".to_owned(), "".to_owned(), date(1), date(14)));
+        db_state.add_sprint(Sprint::new(
+            "".to_owned(),
+            "".to_owned(),
+            date(29),
+            date(31),
+        ));
+        let second = db_state.add_sprint(Sprint::new(
+            "".to_owned(),
+            "".to_owned(),
+            date(15),
+            date(28),
+        ));
+        (db_state, first, second, story_ids)
+    }
+
+    #[test]
+    fn add_story_to_sprint_should_allow_one_open_sprint_per_story() {
+        let (mut db_state, first, second, story_ids) = get_sprint_state();
+
+        db_state.add_story_to_sprint(first, story_ids[0]).unwrap();
+        db_state.add_story_to_sprint(first, story_ids[0]).unwrap();
+        assert_eq!(db_state.sprints[&first].stories, vec![story_ids[0]]);
+
+        assert!(db_state.add_story_to_sprint(second, story_ids[0]).is_err());
+        assert!(db_state.add_story_to_sprint(first, 999).is_err());
+        assert!(db_state.add_story_to_sprint(999, story_ids[1]).is_err());
+
+        db_state
+            .remove_story_from_sprint(first, story_ids[0])
+            .unwrap();
+        assert!(db_state
+            .remove_story_from_sprint(first, story_ids[0])
+            .is_err());
+        db_state.add_story_to_sprint(second, story_ids[0]).unwrap();
+    }
+
+    #[test]
+    fn close_sprint_should_carry_unfinished_stories_over() {
+        let (mut db_state, first, second, story_ids) = get_sprint_state();
+        for story_id in &story_ids {
+            db_state.add_story_to_sprint(first, *story_id).unwrap();
+        }
+
+        assert_eq!(db_state.get_next_sprint(first), Some(second));
+        assert_eq!(db_state.close_sprint(first, Some(second)).unwrap(), 2);
+
+        assert!(db_state.sprints[&first].closed);
+        assert_eq!(db_state.sprints[&first].stories, story_ids);
+        assert_eq!(
+            db_state.sprints[&second].stories,
+            vec![story_ids[0], story_ids[2]]
+        );
+        assert!(db_state.add_story_to_sprint(first, story_ids[1]).is_err());
+        assert!(db_state.close_sprint(first, None).is_err());
+        assert_ne!(db_state.get_next_sprint(second), Some(first));
+    }
+
+    #[test]
+    fn timers_should_log_time_when_stopped() {
+        let (mut db_state, _, _, story_ids) = get_sprint_state();
+        let started_at = Local::now();
+
+        db_state
+            .start_timer(story_ids[0], "ann", started_at)
+            .unwrap();
+        assert!(db_state
+            .start_timer(story_ids[1], "ann", started_at)
+            .is_err());
+        db_state
+            .start_timer(story_ids[1], "bob", started_at)
+            .unwrap();
+        assert!(db_state.start_timer(999, "carol", started_at).is_err());
+        assert!(db_state.get_timer(story_ids[0], "ann").is_some());
+        assert!(db_state
+            .stop_timer(story_ids[0], "bob", started_at)
+            .is_err());
+
+        let worklog = db_state
+            .stop_timer(
+                story_ids[0],
+                "ann",
+                started_at + chrono::Duration::seconds(90 * 60 + 40),
+            )
+            .unwrap();
+        assert_eq!(worklog.minutes, 91);
+        assert_eq!(worklog.user, "ann");
+        assert!(db_state.get_timer(story_ids[0], "ann").is_none());
+
+        // a timer stopped straight away still logs a minute
+        let worklog = db_state
+            .stop_timer(story_ids[1], "bob", started_at)
+            .unwrap();
+        assert_eq!(worklog.minutes, 1);
+        assert!(db_state.timers.is_empty());
+    }
+
+    #[test]
+    fn worklogs_should_add_up_per_story_and_epic() {
+        let (mut db_state, _, _, story_ids) = get_sprint_state();
+        let worklog = |story_id, minutes| Worklog {
+            story_id,
+            user: "ann".to_owned(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            minutes,
+            note: "".to_owned(),
+            story_name: "".to_owned(),
+        };
+
+        db_state.add_worklog(worklog(story_ids[0], 30)).unwrap();
+        db_state.add_worklog(worklog(story_ids[0], 45)).unwrap();
+        db_state.add_worklog(worklog(story_ids[1], 60)).unwrap();
+        assert!(db_state.add_worklog(worklog(story_ids[1], 0)).is_err());
+        assert!(db_state.add_worklog(worklog(999, 10)).is_err());
+
+        let epic_id = db_state.get_epic_id(story_ids[0]).unwrap();
+        assert_eq!(db_state.get_story_minutes(story_ids[0]), 75);
+        assert_eq!(db_state.get_epic_minutes(epic_id), 135);
+        assert_eq!(db_state.get_epic_minutes(999), 0);
+
+        // deleting a story keeps its time
+        db_state.stories.get_mut(&story_ids[0]).unwrap().name = "Apple".to_owned();
+        db_state
+            .start_timer(story_ids[0], "bob", Local::now())
+            .unwrap();
+        assert!(db_state.remove_story(story_ids[0]).is_err());
+        db_state.timers.clear();
+        db_state.epics.get_mut(&epic_id).unwrap().stories.remove(0);
+        db_state.remove_story(story_ids[0]).unwrap();
+        assert_eq!(db_state.get_epic_minutes(epic_id), 60);
+        assert_eq!(db_state.worklogs.len(), 3);
+        assert_eq!(db_state.worklogs[0].story_name, "Apple");
+        assert!(db_state.is_item_id_used(story_ids[0]));
+    }
+
+    #[test]
+    fn add_story_link_should_reject_cycles() {
+        let (mut db_state, _, _, ids) = get_sprint_state();
+
+        db_state
+            .add_story_link(ids[0], LinkKind::Blocks, ids[1])
+            .unwrap();
+        db_state
+            .add_story_link(ids[2], LinkKind::BlockedBy, ids[1])
+            .unwrap();
+
+        let error = db_state
+            .add_story_link(ids[2], LinkKind::Blocks, ids[0])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "link would create a cycle: {} blocks {} blocks {} blocks {}",
+                ids[2], ids[0], ids[1], ids[2]
+            )
+        );
+        assert!(db_state
+            .add_story_link(ids[0], LinkKind::BlockedBy, ids[2])
+            .is_err());
+        assert!(db_state
+            .add_story_link(ids[1], LinkKind::Blocks, ids[0])
+            .is_err());
+        db_state
+            .add_story_link(ids[2], LinkKind::RelatesTo, ids[0])
+            .unwrap();
+        assert!(db_state
+            .add_story_link(ids[0], LinkKind::RelatesTo, ids[1])
+            .is_err());
+        assert!(db_state
+            .add_story_link(ids[0], LinkKind::Blocks, ids[0])
+            .is_err());
+        assert!(db_state
+            .add_story_link(ids[0], LinkKind::Blocks, 999)
+            .is_err());
+
+        assert_eq!(
+            db_state.get_story_links(ids[1]),
+            vec![(LinkKind::BlockedBy, ids[0]), (LinkKind::Blocks, ids[2])]
+        );
+        assert_eq!(
+            db_state.get_story_links(ids[0]),
+            vec![(LinkKind::Blocks, ids[1]), (LinkKind::RelatesTo, ids[2])]
+        );
+
+        db_state.remove_story_link(ids[1], ids[0]).unwrap();
+        assert!(db_state.remove_story_link(ids[1], ids[0]).is_err());
+        db_state
+            .add_story_link(ids[1], LinkKind::Blocks, ids[0])
+            .unwrap();
+        db_state.remove_story(ids[2]).unwrap();
+        assert_eq!(
+            db_state.story_links,
+            vec![StoryLink {
+                from: ids[1],
+                to: ids[0],
+                kind: LinkKind::Blocks
+            }]
+        );
+    }
+
+    #[test]
+    fn get_open_blockers_should_skip_finished_stories() {
+        let (mut db_state, _, _, ids) = get_sprint_state();
+        db_state
+            .add_story_link(ids[2], LinkKind::BlockedBy, ids[0])
+            .unwrap();
+        db_state
+            .add_story_link(ids[2], LinkKind::BlockedBy, ids[1])
+            .unwrap();
+
+        // ids[1] is resolved
+        assert_eq!(db_state.get_open_blockers(ids[2]), vec![ids[0]]);
+        assert!(db_state.get_open_blockers(ids[0]).is_empty());
+        assert!(db_state.is_blocked(ids[2]));
+        assert!(!db_state.is_blocked(ids[0]));
+
+        db_state
+            .set_story_status(ids[2], Status::Closed, NaiveDate::MIN)
+            .unwrap();
+        assert!(!db_state.is_blocked(ids[2]));
+    }
+
+    #[test]
+    fn add_project_should_validate_the_key() {
+        let (mut db_state, ..) = get_sprint_state();
+
+        let project_id = db_state
+            .add_project(Project::new("Website".to_owned(), " web2 ".to_owned()))
+            .unwrap();
+        assert_eq!(db_state.projects[&project_id].key, "WEB2");
+        assert_eq!(db_state.get_project_by_key("web2"), Some(project_id));
+        assert_eq!(db_state.get_project_by_key("WEB"), None);
+
+        for key in ["", "2WEB", "WE B", "web2"] {
+            let project = Project::new("".to_owned(), key.to_owned());
+            assert!(db_state.add_project(project).is_err(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn get_project_view_should_keep_only_the_project() {
+        let (mut db_state, first, second, ids) = get_sprint_state();
+        let epic_id = db_state.get_epic_id(ids[0]).unwrap();
+        let other_epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        let other_story_id = db_state
+            .add_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+            .unwrap();
+        db_state.add_story_to_sprint(first, other_story_id).unwrap();
+        db_state
+            .add_story_link(ids[0], LinkKind::Blocks, other_story_id)
+            .unwrap();
+        let project_id = db_state
+            .add_project(Project::new("".to_owned(), "WEB".to_owned()))
+            .unwrap();
+        db_state.move_epic(epic_id, project_id).unwrap();
+        db_state.sprints.get_mut(&first).unwrap().project_id = Some(project_id);
+        assert_eq!(db_state.get_project_id(epic_id), Some(project_id));
+        assert_eq!(db_state.get_project_id(other_epic_id), None);
+
+        assert!(db_state.clone().get_project_view(999).is_err());
+        let view = db_state.get_project_view(project_id).unwrap();
+        assert_eq!(view.epics.keys().collect::<Vec<_>>(), vec![&epic_id]);
+        assert_eq!(view.stories.len(), ids.len());
+        assert!(!view.sprints[&first].stories.contains(&other_story_id));
+        assert!(!view.sprints.contains_key(&second));
+        assert!(view.story_links.is_empty());
+    }
+
+    #[test]
+    fn status_should_parse_from_str() {
+        assert_eq!("1".parse::<Status>().unwrap(), Status::Open);
+        assert_eq!("In Progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("RESOLVED".parse::<Status>().unwrap(), Status::Resolved);
+        assert_eq!("closed".parse::<Status>().unwrap(), Status::Closed);
+        assert!("done".parse::<Status>().is_err());
+    }
 }
@@ -3,8 +3,12 @@ use std::rc::Rc;
 
 use crate::{
     db::JiraDatabase,
-    models::Action,
-    ui::{EpicDetail, HomePage, Page, Prompts, StoryDetail},
+    models::{Action, BlockedError, Epic, Story},
+    time_tracking::current_user,
+    ui::{
+        Board, Chart, EpicDetail, HomePage, Overdue, Page, Projects, Prompts, SprintDetail,
+        Sprints, StatsDashboard, StoryDetail,
+    },
 };
 
 pub struct Navigator {
@@ -16,23 +20,21 @@ pub struct Navigator {
 impl Navigator {
     pub fn new(db: Rc<JiraDatabase>) -> Self {
         Self {
-            pages: vec![Box::new(HomePage { db: Rc::clone(&db) })],
+            pages: vec![Box::new(Projects::new(Rc::clone(&db)))],
             prompts: Prompts::new(),
             db,
         }
     }
 
-    pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
-        self.pages.last()
+    pub fn get_current_page(&self) -> Option<&dyn Page> {
+        self.pages.last().map(|page| page.as_ref())
     }
 
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
         match action {
             Action::NavigateToEpicDetail { epic_id } => {
-                self.pages.push(Box::new(EpicDetail {
-                    db: Rc::clone(&self.db),
-                    epic_id,
-                }));
+                self.pages
+                    .push(Box::new(EpicDetail::new(epic_id, Rc::clone(&self.db))));
             }
             Action::NavigateToStoryDetail { epic_id, story_id } => {
                 self.pages.push(Box::new(StoryDetail {
@@ -41,15 +43,76 @@ impl Navigator {
                     story_id,
                 }));
             }
+            Action::NavigateToBoard { epic_id } => {
+                self.pages.push(Box::new(Board {
+                    db: Rc::clone(&self.db),
+                    epic_id,
+                }));
+            }
+            Action::NavigateToOverdue => {
+                self.pages.push(Box::new(Overdue::new(Rc::clone(&self.db))));
+            }
+            Action::NavigateToSprints => {
+                self.pages.push(Box::new(Sprints::new(Rc::clone(&self.db))));
+            }
+            Action::NavigateToSprintDetail { sprint_id } => {
+                self.pages
+                    .push(Box::new(SprintDetail::new(sprint_id, Rc::clone(&self.db))));
+            }
+            Action::NavigateToChart { scope } => {
+                self.pages
+                    .push(Box::new(Chart::new(scope, Rc::clone(&self.db))));
+            }
+            Action::NavigateToStats => {
+                self.pages
+                    .push(Box::new(StatsDashboard::new(Rc::clone(&self.db))));
+            }
+            Action::NavigateToProject { project_id } => {
+                self.db.set_current_project(project_id);
+                self.pages
+                    .push(Box::new(HomePage::new(Rc::clone(&self.db))));
+            }
             Action::NavigateToPreviousPage => {
-                if self.pages.len() > 0 {
+                if !self.pages.is_empty() {
                     self.pages.pop();
                 }
+                // back on the project picker, which lists every project
+                if self
+                    .get_current_page()
+                    .is_some_and(|page| page.as_any().is::<Projects>())
+                {
+                    self.db.set_current_project(None);
+                }
+            }
+            Action::CreateProject => {
+                let project = (self.prompts.create_project)();
+                self.db
+                    .create_project(project)
+                    .context("Failed to create project")?;
             }
             Action::CreateEpic => {
                 let epic = (self.prompts.create_epic)();
                 self.db.create_epic(epic).context("Failed to create epic")?;
             }
+            Action::CreateEpicInEditor => {
+                if let Some(epic) =
+                    (self.prompts.edit_epic)(&Epic::new("".to_owned(), "".to_owned()))
+                {
+                    self.db.create_epic(epic).context("Failed to create epic")?;
+                }
+            }
+            Action::EditEpic { epic_id } => {
+                let db_state = self.db.read_db()?;
+                let epic = db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic!"))?;
+                if let Some(epic) = (self.prompts.edit_epic)(epic) {
+                    self.db
+                        .update_epic(epic_id, epic)
+                        .with_context(|| anyhow!("Failed to update epic: {}", epic_id))?;
+                }
+            }
             Action::UpdateEpicStatus { epic_id } => {
                 let new_status = (self.prompts.update_status)();
                 if let Some(status) = new_status {
@@ -58,6 +121,13 @@ impl Navigator {
                         .context("Failed to update epic status")?;
                 }
             }
+            Action::UpdateEpicDueDate { epic_id } => {
+                if let Some(due_date) = (self.prompts.update_due_date)() {
+                    self.db
+                        .update_epic_due_date(epic_id, due_date)
+                        .context("Failed to update epic due date")?;
+                }
+            }
             Action::DeleteEpic { epic_id } => {
                 if (self.prompts.delete_epic)() {
                     self.db
@@ -70,32 +140,167 @@ impl Navigator {
                     }
                 }
             }
+            Action::MoveEpic { epic_id } => {
+                if let Some(project_id) = (self.prompts.select_project)() {
+                    self.db
+                        .move_epic(epic_id, project_id)
+                        .with_context(|| anyhow!("Failed to move epic: {}", epic_id))?;
+
+                    // the epic is no longer in the project being shown
+                    if self
+                        .db
+                        .get_current_project()
+                        .is_some_and(|id| id != project_id)
+                    {
+                        self.pages.pop();
+                    }
+                }
+            }
             Action::CreateStory { epic_id } => {
                 let story = (self.prompts.create_story)();
                 self.db
                     .create_story(story, epic_id)
                     .with_context(|| anyhow!("Failed to create story under epic: {}", epic_id))?;
             }
+            Action::CreateStoryInEditor { epic_id } => {
+                if let Some(story) =
+                    (self.prompts.edit_story)(&Story::new("".to_owned(), "".to_owned()))
+                {
+                    self.db.create_story(story, epic_id).with_context(|| {
+                        anyhow!("Failed to create story under epic: {}", epic_id)
+                    })?;
+                }
+            }
+            Action::EditStory { story_id } => {
+                let db_state = self.db.read_db()?;
+                let story = db_state
+                    .stories
+                    .get(&story_id)
+                    .ok_or_else(|| anyhow!("could not find story!"))?;
+                if let Some(story) = (self.prompts.edit_story)(story) {
+                    // once the status is forced update_story() has no status change to check
+                    self.warn_if_blocked(self.db.update_story(story_id, story.clone()), || {
+                        self.db.force_story_status(story_id, story.status.clone())?;
+                        self.db.update_story(story_id, story)
+                    })
+                    .with_context(|| anyhow!("Failed to update story: {}", story_id))?;
+                }
+            }
             Action::UpdateStoryStatus { story_id } => {
                 let status = (self.prompts.update_status)();
 
                 if let Some(status) = status {
+                    self.warn_if_blocked(
+                        self.db.update_story_status(story_id, status.clone()),
+                        || self.db.force_story_status(story_id, status),
+                    )
+                    .with_context(|| anyhow!("Failed to update story status"))?;
+                }
+            }
+            Action::UpdateStoryDueDate { story_id } => {
+                if let Some(due_date) = (self.prompts.update_due_date)() {
                     self.db
-                        .update_story_status(story_id, status)
-                        .with_context(|| anyhow!("Failed to update story status"))?;
+                        .update_story_due_date(story_id, due_date)
+                        .context("Failed to update story due date")?;
                 }
             }
+            Action::MoveStory { story_id, status } => {
+                self.warn_if_blocked(
+                    self.db.update_story_status(story_id, status.clone()),
+                    || self.db.force_story_status(story_id, status),
+                )
+                .with_context(|| anyhow!("Failed to move story: {}", story_id))?;
+            }
+            Action::CycleSortKey { page } => {
+                let mut sort_order = self.db.get_sort_order(page)?;
+                sort_order.key = sort_order.key.next();
+                self.db
+                    .update_sort_order(page, sort_order)
+                    .context("Failed to update sort order")?;
+            }
+            Action::ToggleSortDirection { page } => {
+                let mut sort_order = self.db.get_sort_order(page)?;
+                sort_order.direction = sort_order.direction.reversed();
+                self.db
+                    .update_sort_order(page, sort_order)
+                    .context("Failed to update sort order")?;
+            }
             Action::DeleteStory { epic_id, story_id } => {
                 if (self.prompts.delete_story)() {
                     self.db.delete_story(epic_id, story_id).with_context(|| {
                         anyhow!("Failed to delete story under epic id: {}", epic_id)
                     })?;
 
-                    if !self.pages.len() > 0 {
+                    if !self.pages.is_empty() {
                         self.pages.pop();
                     }
                 }
             }
+            Action::CreateSprint => {
+                let sprint = (self.prompts.create_sprint)();
+                self.db
+                    .create_sprint(sprint)
+                    .context("Failed to create sprint")?;
+            }
+            Action::AddStoryToSprint { sprint_id } => {
+                if let Some(story_id) = (self.prompts.select_story)() {
+                    self.db
+                        .add_story_to_sprint(sprint_id, story_id)
+                        .with_context(|| anyhow!("Failed to add story to sprint: {}", sprint_id))?;
+                }
+            }
+            Action::RemoveStoryFromSprint { sprint_id } => {
+                if let Some(story_id) = (self.prompts.select_story)() {
+                    self.db
+                        .remove_story_from_sprint(sprint_id, story_id)
+                        .with_context(|| {
+                            anyhow!("Failed to remove story from sprint: {}", sprint_id)
+                        })?;
+                }
+            }
+            Action::CloseSprint { sprint_id } => {
+                let db_state = self.db.read_db()?;
+                let unfinished = db_state.get_unfinished_stories(sprint_id).len();
+                let next_sprint_id = db_state.get_next_sprint(sprint_id);
+                let next_sprint_name = next_sprint_id.map(|id| db_state.sprints[&id].name.as_str());
+                if let Some(carry_over) = (self.prompts.close_sprint)(unfinished, next_sprint_name)
+                {
+                    self.db
+                        .close_sprint(sprint_id, next_sprint_id.filter(|_| carry_over))
+                        .with_context(|| anyhow!("Failed to close sprint: {}", sprint_id))?;
+                }
+            }
+            Action::StartTimer { story_id } => {
+                self.db
+                    .start_timer(story_id, &current_user())
+                    .context("Failed to start timer")?;
+            }
+            Action::StopTimer { story_id } => {
+                self.db
+                    .stop_timer(story_id, &current_user())
+                    .context("Failed to stop timer")?;
+            }
+            Action::LogWork { story_id } => {
+                if let Some(worklog) = (self.prompts.log_work)(story_id) {
+                    self.db
+                        .add_worklog(worklog)
+                        .with_context(|| anyhow!("Failed to log work on story: {}", story_id))?;
+                }
+            }
+            Action::AddStoryLink { story_id } => {
+                if let Some((kind, other_id)) = (self.prompts.link_story)() {
+                    self.db
+                        .add_story_link(story_id, kind, other_id)
+                        .with_context(|| anyhow!("Failed to link story: {}", story_id))?;
+                }
+            }
+            Action::RemoveStoryLink { story_id } => {
+                if let Some(other_id) = (self.prompts.select_story)() {
+                    self.db
+                        .remove_story_link(story_id, other_id)
+                        .with_context(|| anyhow!("Failed to unlink story: {}", story_id))?;
+                }
+            }
             Action::Exit => {
                 // uncertain why removing home page here but test requires empty pages vector
                 // after Exit
@@ -106,12 +311,33 @@ impl Navigator {
         Ok(())
     }
 
+    // A BlockedError from starting a story becomes a warning, force makes the change anyway if the
+    // user goes ahead
+    fn warn_if_blocked(
+        &self,
+        result: Result<()>,
+        force: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let Err(error) = result else {
+            return Ok(());
+        };
+        let Some(blocked) = error.downcast_ref::<BlockedError>() else {
+            return Err(error);
+        };
+        if (self.prompts.start_blocked_story)(&blocked.blockers) {
+            force()?;
+        }
+        Ok(())
+    }
+
     // Private functions used for testing
 
+    #[cfg(test)]
     fn get_page_count(&self) -> usize {
         self.pages.len()
     }
 
+    #[cfg(test)]
     fn set_prompts(&mut self, prompts: Prompts) {
         self.prompts = prompts;
     }
@@ -122,27 +348,26 @@ mod tests {
     use super::*;
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Status, Story},
+        models::{
+            ChartScope, LinkKind, ListPage, Project, SortDirection, SortKey, SortOrder, Sprint,
+            Status, Worklog,
+        },
     };
 
     #[test]
-    fn should_start_on_home_page() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+    fn should_start_on_project_picker() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let nav = Navigator::new(db);
         assert_eq!(nav.get_page_count(), 1);
 
         let current_page = nav.get_current_page().unwrap();
-        let home_page = current_page.as_any().downcast_ref::<HomePage>();
-        assert_eq!(home_page.is_some(), true);
+        let projects_page = current_page.as_any().downcast_ref::<Projects>();
+        assert!(projects_page.is_some());
     }
 
     #[test]
     fn handle_action_should_navigate_pages() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(db);
         nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -151,7 +376,7 @@ mod tests {
 
         let current_page = nav.get_current_page().unwrap();
         let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
-        assert_eq!(epic_detail_page.is_some(), true);
+        assert!(epic_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToStoryDetail {
             epic_id: 1,
@@ -162,21 +387,21 @@ mod tests {
 
         let current_page = nav.get_current_page().unwrap();
         let story_detail_page = current_page.as_any().downcast_ref::<StoryDetail>();
-        assert_eq!(story_detail_page.is_some(), true);
+        assert!(story_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 2);
 
         let current_page = nav.get_current_page().unwrap();
         let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
-        assert_eq!(epic_detail_page.is_some(), true);
+        assert!(epic_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 1);
 
         let current_page = nav.get_current_page().unwrap();
-        let home_page = current_page.as_any().downcast_ref::<HomePage>();
-        assert_eq!(home_page.is_some(), true);
+        let projects_page = current_page.as_any().downcast_ref::<Projects>();
+        assert!(projects_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 0);
@@ -185,11 +410,90 @@ mod tests {
         assert_eq!(nav.get_page_count(), 0);
     }
 
+    #[test]
+    fn handle_action_should_scope_pages_to_the_project() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.create_project = Box::new(|| Project::new("Website".to_owned(), "web".to_owned()));
+        prompts.create_epic = Box::new(|| Epic::new("".to_owned(), "".to_owned()));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateProject).unwrap();
+        assert!(nav.handle_action(Action::CreateProject).is_err());
+        let project_id = db.read_db().unwrap().get_sorted_projects()[0].0;
+        nav.handle_action(Action::NavigateToProject {
+            project_id: Some(project_id),
+        })
+        .unwrap();
+        assert_eq!(db.get_current_project(), Some(project_id));
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<HomePage>().is_some());
+
+        nav.handle_action(Action::CreateEpic).unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 1);
+        assert_eq!(db_state.projects[&project_id].epics.len(), 1);
+
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(db.get_current_project(), None);
+    }
+
+    #[test]
+    fn handle_action_should_move_epics_between_projects() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let first_id = db
+            .create_project(Project::new("".to_owned(), "ONE".to_owned()))
+            .unwrap();
+        let second_id = db
+            .create_project(Project::new("".to_owned(), "TWO".to_owned()))
+            .unwrap();
+        db.set_current_project(Some(first_id));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.select_project = Box::new(move || Some(second_id));
+        nav.set_prompts(prompts);
+        nav.handle_action(Action::NavigateToProject {
+            project_id: Some(first_id),
+        })
+        .unwrap();
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        nav.handle_action(Action::MoveEpic { epic_id }).unwrap();
+
+        // the epic left the project being shown so its page is closed
+        assert_eq!(nav.get_page_count(), 2);
+        assert!(db.read_db().unwrap().epics.is_empty());
+        db.set_current_project(Some(second_id));
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].stories, vec![story_id]);
+        assert!(db_state.stories.contains_key(&story_id));
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_board() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+        nav.handle_action(Action::NavigateToBoard { epic_id: Some(1) })
+            .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let board = current_page.as_any().downcast_ref::<Board>();
+        assert_eq!(board.map(|board| board.epic_id), Some(Some(1)));
+    }
+
     #[test]
     fn handle_action_should_clear_pages_on_exit() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(db);
 
@@ -207,9 +511,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_create_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(Rc::clone(&db));
 
@@ -230,9 +532,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_update_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -256,9 +556,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_delete_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -278,9 +576,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_create_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -304,9 +600,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_update_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -332,10 +626,151 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_handle_delete_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_handle_epics_in_editor() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.edit_epic = Box::new(|epic| {
+            let mut epic = epic.clone();
+            epic.name.push_str("edited");
+            Some(epic)
+        });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateEpicInEditor).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let (epic_id, epic) = db_state.epics.into_iter().next().unwrap();
+        assert_eq!(epic.name, "edited");
+
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        nav.handle_action(Action::EditEpic { epic_id }).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let epic = db_state.epics.get(&epic_id).unwrap();
+        assert_eq!(epic.name, "editededited");
+        assert_eq!(epic.stories, vec![story_id]);
+
+        assert!(nav
+            .handle_action(Action::EditEpic { epic_id: 999 })
+            .is_err());
+    }
+
+    #[test]
+    fn handle_action_should_handle_stories_in_editor() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.edit_story = Box::new(|story| {
+            let mut story = story.clone();
+            story.description.push_str("edited");
+            story.status = Status::Resolved;
+            Some(story)
         });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateStoryInEditor { epic_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let story_id = db_state.epics.get(&epic_id).unwrap().stories[0];
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().description,
+            "edited"
+        );
+
+        nav.handle_action(Action::EditStory { story_id }).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.description, "editededited");
+        assert_eq!(story.status, Status::Resolved);
+    }
+
+    #[test]
+    fn handle_action_should_not_save_cancelled_edits() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.edit_epic = Box::new(|_| None);
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateEpicInEditor).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics.is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_handle_move_story() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::MoveStory {
+            story_id,
+            status: Status::Resolved,
+        })
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().status,
+            Status::Resolved
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_sort_order_changes() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::CycleSortKey {
+            page: ListPage::Home,
+        })
+        .unwrap();
+        nav.handle_action(Action::ToggleSortDirection {
+            page: ListPage::Home,
+        })
+        .unwrap();
+
+        assert_eq!(
+            db.get_sort_order(ListPage::Home).unwrap(),
+            SortOrder {
+                key: SortKey::Name,
+                direction: SortDirection::Descending,
+            }
+        );
+        assert_eq!(
+            db.get_sort_order(ListPage::EpicDetail).unwrap(),
+            SortOrder::default()
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_delete_story() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -356,4 +791,277 @@ mod tests {
         let db_state = db.read_db().unwrap();
         assert_eq!(db_state.stories.len(), 0);
     }
+
+    #[test]
+    fn handle_action_should_navigate_to_chart() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+        nav.handle_action(Action::NavigateToChart {
+            scope: ChartScope::Epic(1),
+        })
+        .unwrap();
+
+        let current_page = nav.get_current_page().unwrap();
+        let chart = current_page.as_any().downcast_ref::<Chart>();
+        assert_eq!(chart.map(|chart| chart.scope), Some(ChartScope::Epic(1)));
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_stats() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+        nav.handle_action(Action::NavigateToStats).unwrap();
+
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page
+            .as_any()
+            .downcast_ref::<StatsDashboard>()
+            .is_some());
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_overdue() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+        nav.handle_action(Action::NavigateToOverdue).unwrap();
+
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<Overdue>().is_some());
+    }
+
+    #[test]
+    fn handle_action_should_update_due_dates() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let due_date = chrono::NaiveDate::from_ymd_opt(2024, 2, 1);
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.update_due_date = Box::new(move || Some(due_date));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicDueDate { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryDueDate { story_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].due_date, due_date);
+        assert_eq!(db_state.stories[&story_id].due_date, due_date);
+
+        let mut prompts = Prompts::new();
+        prompts.update_due_date = Box::new(|| Some(None));
+        nav.set_prompts(prompts);
+        nav.handle_action(Action::UpdateStoryDueDate { story_id })
+            .unwrap();
+        assert_eq!(db.read_db().unwrap().stories[&story_id].due_date, None);
+    }
+
+    #[test]
+    fn handle_action_should_track_time() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.log_work = Box::new(|story_id| {
+            Some(Worklog {
+                story_id,
+                user: "ann".to_owned(),
+                date: chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                minutes: 90,
+                note: "".to_owned(),
+                story_name: "".to_owned(),
+            })
+        });
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::LogWork { story_id }).unwrap();
+        nav.handle_action(Action::StartTimer { story_id }).unwrap();
+        assert!(nav.handle_action(Action::StartTimer { story_id }).is_err());
+        assert!(db
+            .read_db()
+            .unwrap()
+            .get_timer(story_id, &current_user())
+            .is_some());
+        nav.handle_action(Action::StopTimer { story_id }).unwrap();
+        assert!(nav.handle_action(Action::StopTimer { story_id }).is_err());
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.timers.is_empty());
+        assert_eq!(db_state.worklogs.len(), 2);
+        assert_eq!(db_state.get_story_minutes(story_id), 91);
+    }
+
+    #[test]
+    fn handle_action_should_link_stories() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.link_story = Box::new(move || Some((LinkKind::BlockedBy, blocker_id)));
+        prompts.select_story = Box::new(move || Some(blocker_id));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::AddStoryLink { story_id })
+            .unwrap();
+        assert!(nav
+            .handle_action(Action::AddStoryLink { story_id })
+            .is_err());
+        assert_eq!(
+            db.read_db().unwrap().get_open_blockers(story_id),
+            vec![blocker_id]
+        );
+
+        nav.handle_action(Action::RemoveStoryLink { story_id })
+            .unwrap();
+        assert!(db.read_db().unwrap().story_links.is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_warn_before_starting_blocked_stories() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_story_link(story_id, LinkKind::BlockedBy, blocker_id)
+            .unwrap();
+
+        let warnings = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let go_ahead = Rc::new(std::cell::Cell::new(false));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        let warned = Rc::clone(&warnings);
+        let answer = Rc::clone(&go_ahead);
+        prompts.start_blocked_story = Box::new(move |blockers| {
+            warned.borrow_mut().push(blockers.to_vec());
+            answer.get()
+        });
+        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.edit_story = Box::new(|story| {
+            let mut story = story.clone();
+            story.name = "Started".to_owned();
+            story.status = Status::InProgress;
+            Some(story)
+        });
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryStatus { story_id })
+            .unwrap();
+        nav.handle_action(Action::MoveStory {
+            story_id,
+            status: Status::InProgress,
+        })
+        .unwrap();
+        assert_eq!(*warnings.borrow(), vec![vec![blocker_id], vec![blocker_id]]);
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::Open
+        );
+
+        // going ahead saves the edit along with the status
+        go_ahead.set(true);
+        nav.handle_action(Action::EditStory { story_id }).unwrap();
+        let story = db.read_db().unwrap().stories[&story_id].clone();
+        assert_eq!(
+            (story.name.as_str(), story.status),
+            ("Started", Status::InProgress)
+        );
+        assert_eq!(warnings.borrow().len(), 3);
+
+        // no warning once the blocker is done, or for other statuses
+        nav.handle_action(Action::MoveStory {
+            story_id,
+            status: Status::Resolved,
+        })
+        .unwrap();
+        db.update_story_status(blocker_id, Status::Closed).unwrap();
+        nav.handle_action(Action::UpdateStoryStatus { story_id })
+            .unwrap();
+        assert_eq!(warnings.borrow().len(), 3);
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn handle_action_should_plan_and_close_sprints() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut prompts = Prompts::new();
+        prompts.create_sprint = Box::new(|| {
+            let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            Sprint::new("sprint".to_owned(), "".to_owned(), date, date)
+        });
+        prompts.select_story = Box::new(move || Some(story_id));
+        prompts.close_sprint = Box::new(|unfinished, next_sprint| {
+            assert_eq!((unfinished, next_sprint), (1, Some("sprint")));
+            Some(true)
+        });
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToSprints).unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<Sprints>().is_some());
+
+        nav.handle_action(Action::CreateSprint).unwrap();
+        nav.handle_action(Action::CreateSprint).unwrap();
+        let (first, second) = (3, 4);
+        nav.handle_action(Action::NavigateToSprintDetail { sprint_id: first })
+            .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page
+            .as_any()
+            .downcast_ref::<SprintDetail>()
+            .is_some());
+
+        nav.handle_action(Action::AddStoryToSprint { sprint_id: first })
+            .unwrap();
+        nav.handle_action(Action::CloseSprint { sprint_id: first })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.sprints[&first].closed);
+        assert_eq!(db_state.sprints[&second].stories, vec![story_id]);
+
+        nav.handle_action(Action::RemoveStoryFromSprint { sprint_id: second })
+            .unwrap();
+        assert!(db.read_db().unwrap().sprints[&second].stories.is_empty());
+    }
 }
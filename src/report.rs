@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::models::{DBState, Status};
+
+// Builds a Markdown document with a section per epic, listing its stories as a task list, followed
+// by the number of epics and stories in each status. Epics and stories are in the same order as
+// the HomePage and EpicDetail pages list them. epic_id restricts the report to a single epic
+pub fn get_markdown_report(db_state: &DBState, epic_id: Option<u32>) -> Result<String> {
+    let epics: Vec<_> = db_state
+        .get_sorted_epics()
+        .into_iter()
+        .filter(|(id, _)| epic_id.is_none_or(|epic_id| *id == epic_id))
+        .collect();
+    if let Some(epic_id) = epic_id {
+        if epics.is_empty() {
+            return Err(anyhow!("could not find epic with id: {}", epic_id));
+        }
+    }
+
+    let mut epic_counts: HashMap<&Status, usize> = HashMap::new();
+    let mut story_counts: HashMap<&Status, usize> = HashMap::new();
+    let mut report = String::from("# Report\n");
+
+    for (id, epic) in epics {
+        *epic_counts.entry(&epic.status).or_default() += 1;
+
+        write!(report, "\n## {} (#{})\n\n", epic.name, id)?;
+        write!(report, "**Status:** {}\n\n", epic.status)?;
+        if !epic.description.is_empty() {
+            write!(report, "{}\n\n", epic.description)?;
+        }
+
+        let stories = db_state.get_sorted_stories(epic);
+        if stories.is_empty() {
+            report.push_str("_No stories_\n");
+        }
+        for (id, story) in stories {
+            *story_counts.entry(&story.status).or_default() += 1;
+
+            let checkbox = if story.status.is_done() { "x" } else { " " };
+            writeln!(
+                report,
+                "- [{}] {} (#{}) - {}",
+                checkbox, story.name, id, story.status
+            )?;
+        }
+    }
+
+    report.push_str("\n## Summary\n\n| Status | Epics | Stories |\n| --- | --- | --- |\n");
+    for status in Status::ALL.iter() {
+        writeln!(
+            report,
+            "| {} | {} | {} |",
+            status,
+            epic_counts.get(status).unwrap_or(&0),
+            story_counts.get(status).unwrap_or(&0)
+        )?;
+    }
+    writeln!(
+        report,
+        "| **Total** | {} | {} |",
+        epic_counts.values().sum::<usize>(),
+        story_counts.values().sum::<usize>()
+    )?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::models::{Epic, ListPage, SortKey, SortOrder, Story};
+
+    fn get_db_state() -> DBState {
+        let mut fruit = Epic::new("Fruit".to_owned(), "Buy fruit".to_owned());
+        fruit.status = Status::InProgress;
+        fruit.stories = vec![2, 3];
+        let mut apple = Story::new("Apple".to_owned(), "".to_owned());
+        apple.status = Status::Closed;
+
+        DBState {
+            last_item_id: 4,
+            epics: HashMap::from([(1, fruit), (4, Epic::new("Veg".to_owned(), "".to_owned()))]),
+            stories: HashMap::from([
+                (2, Story::new("Banana".to_owned(), "".to_owned())),
+                (3, apple),
+            ]),
+            sort_orders: HashMap::from([(
+                ListPage::EpicDetail,
+                SortOrder {
+                    key: SortKey::Name,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn get_markdown_report_should_list_epics_and_stories() {
+        assert_eq!(
+            get_markdown_report(&get_db_state(), None).unwrap(),
+            "# Report\n\
+             \n\
+             ## Fruit (#1)\n\
+             \n\
+             **Status:** IN PROGRESS\n\
+             \n\
+             Buy fruit\n\
+             \n\
+             - [x] Apple (#3) - CLOSED\n\
+             - [ ] Banana (#2) - OPEN\n\
+             \n\
+             ## Veg (#4)\n\
+             \n\
+             **Status:** OPEN\n\
+             \n\
+             _No stories_\n\
+             \n\
+             ## Summary\n\
+             \n\
+             | Status | Epics | Stories |\n\
+             | --- | --- | --- |\n\
+             | OPEN | 1 | 1 |\n\
+             | IN PROGRESS | 1 | 0 |\n\
+             | RESOLVED | 0 | 0 |\n\
+             | CLOSED | 0 | 1 |\n\
+             | **Total** | 2 | 2 |\n"
+        );
+    }
+
+    #[test]
+    fn get_markdown_report_should_filter_by_epic() {
+        let report = get_markdown_report(&get_db_state(), Some(4)).unwrap();
+        assert!(report.contains("## Veg (#4)"));
+        assert!(!report.contains("Fruit"));
+        assert!(report.contains("| **Total** | 1 | 0 |"));
+
+        assert!(get_markdown_report(&get_db_state(), Some(999)).is_err());
+    }
+}
@@ -0,0 +1,771 @@
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+use schemars::{schema_for, JsonSchema};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::db::JiraDatabase;
+use crate::hooks::VetoError;
+use crate::models::{
+    BlockedError, DBState, Epic, ListPage, NotFoundError, SortOrder, Status, Story,
+};
+
+// JSON-RPC 2.0 error codes, NOT_FOUND, VETOED and BLOCKED are in the range reserved for the
+// application
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const NOT_FOUND: i64 = -32001;
+const VETOED: i64 = -32002;
+const BLOCKED: i64 = -32003;
+
+// sent to the client whenever the database changes, whether through a request or another process
+const CHANGE_NOTIFICATION: &str = "database_changed";
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+pub type Output = Arc<Mutex<dyn Write + Send>>;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    // requests without an id are notifications and get no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+// An error with a JSON-RPC error code, anything else is reported as a server error
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+fn rpc_error(code: i64, message: impl Into<String>) -> anyhow::Error {
+    anyhow!(RpcError {
+        code,
+        message: message.into(),
+    })
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct NoParams {}
+
+#[derive(Deserialize, JsonSchema)]
+struct EpicParams {
+    epic_id: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct StoryParams {
+    story_id: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct DeleteStoryParams {
+    epic_id: u32,
+    story_id: u32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CreateEpicParams {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CreateStoryParams {
+    epic_id: u32,
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct UpdateEpicParams {
+    epic_id: u32,
+    name: String,
+    description: String,
+    status: Status,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct UpdateStoryParams {
+    story_id: u32,
+    name: String,
+    description: String,
+    status: Status,
+    // starts the story even if it is blocked
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct EpicStatusParams {
+    epic_id: u32,
+    status: Status,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct StoryStatusParams {
+    story_id: u32,
+    status: Status,
+    // starts the story even if it is blocked
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct PageParams {
+    page: ListPage,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct SortOrderParams {
+    page: ListPage,
+    sort_order: SortOrder,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct IdResult {
+    id: u32,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct WithId<T> {
+    id: u32,
+    #[serde(flatten)]
+    item: T,
+}
+
+struct Method {
+    name: &'static str,
+    params: Value,
+    result: Value,
+    // whether a successful call changes the database
+    mutates: bool,
+}
+
+fn describe<P: JsonSchema, R: JsonSchema>(name: &'static str, mutates: bool) -> Method {
+    Method {
+        name,
+        params: to_value(schema_for!(P)),
+        result: to_value(schema_for!(R)),
+        mutates,
+    }
+}
+
+// Every method with its schemas, each one has a matching arm in call()
+fn get_methods() -> Vec<Method> {
+    vec![
+        describe::<NoParams, DBState>("read_db", false),
+        describe::<NoParams, Vec<WithId<Epic>>>("list_epics", false),
+        describe::<EpicParams, WithId<Epic>>("get_epic", false),
+        describe::<EpicParams, Vec<WithId<Story>>>("list_stories", false),
+        describe::<StoryParams, WithId<Story>>("get_story", false),
+        describe::<CreateEpicParams, IdResult>("create_epic", true),
+        describe::<CreateStoryParams, IdResult>("create_story", true),
+        describe::<UpdateEpicParams, ()>("update_epic", true),
+        describe::<UpdateStoryParams, ()>("update_story", true),
+        describe::<EpicStatusParams, ()>("update_epic_status", true),
+        describe::<StoryStatusParams, ()>("update_story_status", true),
+        describe::<StoryStatusParams, ()>("move_story", true),
+        describe::<EpicParams, ()>("delete_epic", true),
+        describe::<DeleteStoryParams, ()>("delete_story", true),
+        describe::<PageParams, SortOrder>("get_sort_order", false),
+        describe::<SortOrderParams, ()>("update_sort_order", true),
+        describe::<PageParams, ()>("cycle_sort_key", true),
+        describe::<PageParams, ()>("toggle_sort_direction", true),
+        describe::<NoParams, Value>("rpc.schema", false),
+    ]
+}
+
+// Reads one request per line from input and writes one response per line to output until input
+// ends. When db_path is set the file is watched so changes made by other processes are sent as
+// notifications too
+pub fn run_rpc(
+    db: &JiraDatabase,
+    db_path: Option<PathBuf>,
+    input: impl BufRead,
+    output: Output,
+) -> Result<()> {
+    let last_modified = Arc::new(Mutex::new(None));
+    if let Some(db_path) = db_path.clone() {
+        watch_file(
+            db_path,
+            WATCH_INTERVAL,
+            Arc::clone(&last_modified),
+            Arc::clone(&output),
+        );
+    }
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // held while handling the request so the watcher doesn't report our own changes
+        let mut last_modified = last_modified.lock().map_err(|_| anyhow!("lock poisoned"))?;
+        let (response, changed_by) = handle_line(db, &line);
+        if let Some(response) = response {
+            send(&output, &response)?;
+        }
+        if let Some(method) = changed_by {
+            *last_modified = db_path.as_ref().and_then(get_modified_time);
+            send(&output, &get_change_notification(Some(&method)))?;
+        }
+        // stdout is for the protocol only
+        for message in db.take_hook_output() {
+            eprintln!("{message}");
+        }
+    }
+    Ok(())
+}
+
+// Returns the response, if the request needs one, and the name of the method if it changed the
+// database
+fn handle_line(db: &JiraDatabase, line: &str) -> (Option<Value>, Option<String>) {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                Some(get_error_response(
+                    Value::Null,
+                    &rpc_error(PARSE_ERROR, e.to_string()),
+                )),
+                None,
+            )
+        }
+    };
+
+    // a batch gets a single array with the responses of the requests that need one
+    if let Value::Array(requests) = request {
+        if requests.is_empty() {
+            let error = rpc_error(INVALID_REQUEST, "empty batch");
+            return (Some(get_error_response(Value::Null, &error)), None);
+        }
+        let mut responses = Vec::new();
+        let mut changed_by = None;
+        for request in requests {
+            let (response, changed) = handle_request(db, request);
+            responses.extend(response);
+            changed_by = changed.or(changed_by);
+        }
+        let responses = (!responses.is_empty()).then_some(Value::Array(responses));
+        return (responses, changed_by);
+    }
+
+    handle_request(db, request)
+}
+
+fn handle_request(db: &JiraDatabase, request: Value) -> (Option<Value>, Option<String>) {
+    let request: Request = match serde_json::from_value::<Request>(request) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        Ok(_) => {
+            let error = rpc_error(INVALID_REQUEST, "jsonrpc must be \"2.0\"");
+            return (Some(get_error_response(Value::Null, &error)), None);
+        }
+        Err(e) => {
+            let error = rpc_error(INVALID_REQUEST, e.to_string());
+            return (Some(get_error_response(Value::Null, &error)), None);
+        }
+    };
+
+    let result = call(db, &request.method, request.params);
+    let changed_by = match result {
+        Ok(_) => get_methods()
+            .into_iter()
+            .find(|method| method.name == request.method && method.mutates)
+            .map(|method| method.name.to_owned()),
+        Err(_) => None,
+    };
+
+    let response = request.id.map(|id| match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => get_error_response(id, &e),
+    });
+    (response, changed_by)
+}
+
+fn get_error_response(id: Value, error: &anyhow::Error) -> Value {
+    let code = if let Some(error) = error.downcast_ref::<RpcError>() {
+        error.code
+    } else if error.downcast_ref::<NotFoundError>().is_some() {
+        NOT_FOUND
+    } else if error.downcast_ref::<VetoError>().is_some() {
+        VETOED
+    } else if error.downcast_ref::<BlockedError>().is_some() {
+        BLOCKED
+    } else {
+        SERVER_ERROR
+    };
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": format!("{:#}", error) }
+    })
+}
+
+fn call(db: &JiraDatabase, method: &str, params: Value) -> Result<Value> {
+    let result = match method {
+        "read_db" => {
+            parse_params::<NoParams>(params)?;
+            to_value(db.read_db()?)
+        }
+        "list_epics" => {
+            parse_params::<NoParams>(params)?;
+            let db_state = db.read_db()?;
+            let epics: Vec<_> = db_state
+                .get_sorted_epics()
+                .into_iter()
+                .map(|(id, item)| WithId { id, item })
+                .collect();
+            to_value(epics)
+        }
+        "get_epic" => {
+            let params: EpicParams = parse_params(params)?;
+            let db_state = db.read_db()?;
+            let item = db_state
+                .epics
+                .get(&params.epic_id)
+                .ok_or(NotFoundError("could not find epic in database!"))?;
+            to_value(WithId {
+                id: params.epic_id,
+                item,
+            })
+        }
+        "list_stories" => {
+            let params: EpicParams = parse_params(params)?;
+            let db_state = db.read_db()?;
+            let epic = db_state
+                .epics
+                .get(&params.epic_id)
+                .ok_or(NotFoundError("could not find epic in database!"))?;
+            let stories: Vec<_> = db_state
+                .get_sorted_stories(epic)
+                .into_iter()
+                .map(|(id, item)| WithId { id, item })
+                .collect();
+            to_value(stories)
+        }
+        "get_story" => {
+            let params: StoryParams = parse_params(params)?;
+            let db_state = db.read_db()?;
+            let item = db_state
+                .stories
+                .get(&params.story_id)
+                .ok_or(NotFoundError("could not find story in database!"))?;
+            to_value(WithId {
+                id: params.story_id,
+                item,
+            })
+        }
+        "create_epic" => {
+            let params: CreateEpicParams = parse_params(params)?;
+            let id = db.create_epic(Epic::new(params.name, params.description))?;
+            to_value(IdResult { id })
+        }
+        "create_story" => {
+            let params: CreateStoryParams = parse_params(params)?;
+            let story = Story::new(params.name, params.description);
+            let id = db.create_story(story, params.epic_id)?;
+            to_value(IdResult { id })
+        }
+        "update_epic" => {
+            let params: UpdateEpicParams = parse_params(params)?;
+            let mut epic = Epic::new(params.name, params.description);
+            epic.status = params.status;
+            to_value(db.update_epic(params.epic_id, epic)?)
+        }
+        "update_story" => {
+            let params: UpdateStoryParams = parse_params(params)?;
+            let mut story = Story::new(params.name, params.description);
+            story.status = params.status;
+            if params.force {
+                db.force_story_status(params.story_id, story.status.clone())?;
+            }
+            to_value(db.update_story(params.story_id, story)?)
+        }
+        "update_epic_status" => {
+            let params: EpicStatusParams = parse_params(params)?;
+            to_value(db.update_epic_status(params.epic_id, params.status)?)
+        }
+        "update_story_status" | "move_story" => {
+            let params: StoryStatusParams = parse_params(params)?;
+            if params.force {
+                to_value(db.force_story_status(params.story_id, params.status)?)
+            } else {
+                to_value(db.update_story_status(params.story_id, params.status)?)
+            }
+        }
+        "delete_epic" => {
+            let params: EpicParams = parse_params(params)?;
+            to_value(db.delete_epic(params.epic_id)?)
+        }
+        "delete_story" => {
+            let params: DeleteStoryParams = parse_params(params)?;
+            to_value(db.delete_story(params.epic_id, params.story_id)?)
+        }
+        "get_sort_order" => {
+            let params: PageParams = parse_params(params)?;
+            to_value(db.get_sort_order(params.page)?)
+        }
+        "update_sort_order" => {
+            let params: SortOrderParams = parse_params(params)?;
+            to_value(db.update_sort_order(params.page, params.sort_order)?)
+        }
+        "cycle_sort_key" => {
+            let params: PageParams = parse_params(params)?;
+            let mut sort_order = db.get_sort_order(params.page)?;
+            sort_order.key = sort_order.key.next();
+            to_value(db.update_sort_order(params.page, sort_order)?)
+        }
+        "toggle_sort_direction" => {
+            let params: PageParams = parse_params(params)?;
+            let mut sort_order = db.get_sort_order(params.page)?;
+            sort_order.direction = sort_order.direction.reversed();
+            to_value(db.update_sort_order(params.page, sort_order)?)
+        }
+        "rpc.schema" => {
+            parse_params::<NoParams>(params)?;
+            let methods: serde_json::Map<String, Value> = get_methods()
+                .into_iter()
+                .map(|method| {
+                    (
+                        method.name.to_owned(),
+                        json!({ "params": method.params, "result": method.result }),
+                    )
+                })
+                .collect();
+            Value::Object(methods)
+        }
+        _ => {
+            return Err(rpc_error(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {}", method),
+            ))
+        }
+    };
+    Ok(result)
+}
+
+// missing params are treated as an empty object
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| rpc_error(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn get_change_notification(method: Option<&str>) -> Value {
+    json!({ "jsonrpc": "2.0", "method": CHANGE_NOTIFICATION, "params": { "method": method } })
+}
+
+fn send(output: &Output, message: &Value) -> Result<()> {
+    let mut output = output.lock().map_err(|_| anyhow!("lock poisoned"))?;
+    writeln!(output, "{}", message)?;
+    output.flush()?;
+    Ok(())
+}
+
+// For a directory database the newest time of the directory and every file in it. Rewriting an
+// item file in place, as git checkout does, doesn't change the time of the directory holding it
+fn get_modified_time(path: &PathBuf) -> Option<SystemTime> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok();
+    if !metadata.is_dir() {
+        return modified;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return modified;
+    };
+    entries
+        .filter_map(|entry| get_modified_time(&entry.ok()?.path()))
+        .chain(modified)
+        .max()
+}
+
+// Polls the file's modification time in the background, sending a notification with no method
+// when it changes. last_modified is shared with run_rpc() which updates it after its own changes
+fn watch_file(
+    path: PathBuf,
+    interval: Duration,
+    last_modified: Arc<Mutex<Option<SystemTime>>>,
+    output: Output,
+) {
+    if let Ok(mut last_modified) = last_modified.lock() {
+        *last_modified = get_modified_time(&path);
+    }
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let Ok(mut last_modified) = last_modified.lock() else {
+            return;
+        };
+        let modified = get_modified_time(&path);
+        if modified != *last_modified {
+            *last_modified = modified;
+            if send(&output, &get_change_notification(None)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::models::LinkKind;
+
+    // a writer the test can read back from after handing it to run_rpc()
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn get_lines(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    fn run(db: &JiraDatabase, input: &str) -> Vec<Value> {
+        let buffer = SharedBuffer::default();
+        run_rpc(
+            db,
+            None,
+            input.as_bytes(),
+            Arc::new(Mutex::new(buffer.clone())),
+        )
+        .unwrap();
+        buffer.get_lines()
+    }
+
+    fn get_db() -> JiraDatabase {
+        JiraDatabase::from_database(Box::new(MockDB::new()))
+    }
+
+    #[test]
+    fn run_rpc_should_call_methods_and_notify_changes() {
+        let db = get_db();
+        let input = r#"
+            {"jsonrpc": "2.0", "id": 1, "method": "create_epic", "params": {"name": "Epic"}}
+            {"jsonrpc": "2.0", "id": 2, "method": "create_story", "params": {"epic_id": 1, "name": "Story"}}
+            {"jsonrpc": "2.0", "method": "move_story", "params": {"story_id": 2, "status": "Resolved"}}
+            {"jsonrpc": "2.0", "id": "list", "method": "list_stories", "params": {"epic_id": 1}}
+        "#;
+
+        let lines = run(&db, input);
+
+        assert_eq!(
+            lines,
+            vec![
+                json!({"jsonrpc": "2.0", "id": 1, "result": {"id": 1}}),
+                json!({"jsonrpc": "2.0", "method": "database_changed", "params": {"method": "create_epic"}}),
+                json!({"jsonrpc": "2.0", "id": 2, "result": {"id": 2}}),
+                json!({"jsonrpc": "2.0", "method": "database_changed", "params": {"method": "create_story"}}),
+                json!({"jsonrpc": "2.0", "method": "database_changed", "params": {"method": "move_story"}}),
+                json!({"jsonrpc": "2.0", "id": "list", "result": [
+                    {"id": 2, "name": "Story", "description": "", "status": "Resolved"}
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_rpc_should_return_errors() {
+        let db = get_db();
+        let input = r#"
+            not json
+            {"jsonrpc": "1.0", "id": 1, "method": "read_db"}
+            {"jsonrpc": "2.0", "id": 2, "method": "launch"}
+            {"jsonrpc": "2.0", "id": 3, "method": "get_epic", "params": {"epic": 1}}
+            {"jsonrpc": "2.0", "id": 4, "method": "get_epic", "params": {"epic_id": 1}}
+            {"jsonrpc": "2.0", "id": 5, "method": "update_epic_status", "params": {"epic_id": 1, "status": "Done"}}
+        "#;
+
+        let codes: Vec<_> = run(&db, input)
+            .iter()
+            .map(|line| line["error"]["code"].as_i64().unwrap())
+            .collect();
+
+        assert_eq!(
+            codes,
+            vec![
+                PARSE_ERROR,
+                INVALID_REQUEST,
+                METHOD_NOT_FOUND,
+                INVALID_PARAMS,
+                NOT_FOUND,
+                INVALID_PARAMS
+            ]
+        );
+    }
+
+    #[test]
+    fn run_rpc_should_start_blocked_stories_only_when_forced() {
+        let db = get_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_story_link(story_id, LinkKind::BlockedBy, blocker_id)
+            .unwrap();
+        let input = format!(
+            r#"
+            {{"jsonrpc": "2.0", "id": 1, "method": "move_story", "params": {{"story_id": {0}, "status": "InProgress"}}}}
+            {{"jsonrpc": "2.0", "id": 2, "method": "move_story", "params": {{"story_id": {0}, "status": "InProgress", "force": true}}}}
+        "#,
+            story_id
+        );
+
+        let lines = run(&db, &input);
+
+        assert_eq!(lines[0]["error"]["code"], BLOCKED);
+        assert_eq!(lines[1]["result"], Value::Null);
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn run_rpc_should_handle_batches() {
+        let db = get_db();
+        let input = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "create_epic", "params": {"name": "Epic"}},
+            {"jsonrpc": "2.0", "method": "toggle_sort_direction", "params": {"page": "Home"}},
+            {"jsonrpc": "2.0", "id": 2, "method": "get_sort_order", "params": {"page": "Home"}}
+        ]"#
+        .replace('\n', "");
+
+        let lines = run(&db, &input);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0][1]["result"]["direction"], "Descending");
+        assert_eq!(lines[1]["method"], "database_changed");
+    }
+
+    #[test]
+    fn every_method_should_be_callable_and_have_a_schema() {
+        let db = get_db();
+        for Method { name, params, .. } in get_methods() {
+            let error = call(&db, name, json!({ "invalid": [] })).err();
+            let code = error
+                .as_ref()
+                .and_then(|e| e.downcast_ref::<RpcError>())
+                .map(|e| e.code);
+            assert_ne!(code, Some(METHOD_NOT_FOUND), "{}", name);
+            assert!(params.is_object(), "{}", name);
+        }
+
+        let schema = call(&db, "rpc.schema", Value::Null).unwrap();
+        assert_eq!(
+            schema["update_story_status"]["params"]["required"],
+            json!(["status", "story_id"])
+        );
+        assert!(schema["read_db"]["result"]["definitions"]["Status"].is_object());
+    }
+
+    #[test]
+    fn watch_file_should_notify_changes_to_item_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let epics = dir.path().join("epics");
+        fs::create_dir(&epics).unwrap();
+        let item = fs::File::create(epics.join("12.json")).unwrap();
+        let buffer = SharedBuffer::default();
+        let last_modified = Arc::new(Mutex::new(None));
+
+        watch_file(
+            dir.path().to_path_buf(),
+            Duration::from_millis(5),
+            Arc::clone(&last_modified),
+            Arc::new(Mutex::new(buffer.clone())),
+        );
+        thread::sleep(Duration::from_millis(20));
+        assert!(buffer.get_lines().is_empty());
+
+        // an existing file edited in place leaves the directories' times as they were
+        let modified = SystemTime::now() + Duration::from_secs(10);
+        item.set_modified(modified).unwrap();
+        for _ in 0..100 {
+            if !buffer.get_lines().is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(buffer.get_lines().len(), 1);
+    }
+
+    #[test]
+    fn watch_file_should_notify_external_changes() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let buffer = SharedBuffer::default();
+        let last_modified = Arc::new(Mutex::new(None));
+
+        watch_file(
+            file.path().to_path_buf(),
+            Duration::from_millis(5),
+            Arc::clone(&last_modified),
+            Arc::new(Mutex::new(buffer.clone())),
+        );
+        thread::sleep(Duration::from_millis(20));
+        assert!(buffer.get_lines().is_empty());
+
+        let modified = SystemTime::now() + Duration::from_secs(10);
+        file.as_file().set_modified(modified).unwrap();
+        for _ in 0..100 {
+            if !buffer.get_lines().is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(
+            buffer.get_lines(),
+            vec![
+                json!({"jsonrpc": "2.0", "method": "database_changed", "params": {"method": null}})
+            ]
+        );
+    }
+}
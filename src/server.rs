@@ -0,0 +1,434 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::db::JiraDatabase;
+use crate::hooks::VetoError;
+use crate::models::{BlockedError, Epic, NotFoundError, Status, Story};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+// A request that can never succeed as sent, such as invalid JSON, answered with a 400
+#[derive(Debug)]
+struct BadRequestError(String);
+
+impl Display for BadRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for BadRequestError {}
+
+// an item with its id, which isn't stored in the item itself
+#[derive(Serialize)]
+struct WithId<'a, T> {
+    id: u32,
+    #[serde(flatten)]
+    item: &'a T,
+}
+
+#[derive(Deserialize)]
+struct NewItem {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct StatusUpdate {
+    // anything Status::from_str accepts e.g. "in progress" or "2"
+    status: String,
+    // starts the story even if it is blocked
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ApiResponse {
+    pub status_code: u16,
+    // sent as JSON, responses without a body use 204
+    pub body: Option<Value>,
+}
+
+pub fn start(address: &str) -> Result<Server> {
+    Server::http(address).map_err(|e| anyhow!("failed to listen on {}: {}", address, e))
+}
+
+// Handles requests one at a time until the server is closed
+pub fn run(server: &Server, db: &JiraDatabase) -> Result<()> {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => handle_request(db, request.method(), request.url(), &body),
+            Err(e) => get_error_response(anyhow!(BadRequestError(e.to_string()))),
+        };
+
+        let content_type = Header::from_bytes("Content-Type", "application/json")
+            .map_err(|_| anyhow!("invalid header"))?;
+        let result = match response.body {
+            Some(body) => request.respond(
+                Response::from_string(body.to_string())
+                    .with_status_code(response.status_code)
+                    .with_header(content_type),
+            ),
+            None => request.respond(Response::empty(response.status_code)),
+        };
+        if let Err(e) = result {
+            eprintln!("ERROR: failed to send response: {e}");
+        }
+        for message in db.take_hook_output() {
+            eprintln!("{message}");
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_request(db: &JiraDatabase, method: &Method, url: &str, body: &str) -> ApiResponse {
+    match route(db, method, url, body) {
+        Ok((status_code, body)) => ApiResponse { status_code, body },
+        Err(e) => get_error_response(e),
+    }
+}
+
+fn get_error_response(error: anyhow::Error) -> ApiResponse {
+    let status_code = if error.downcast_ref::<NotFoundError>().is_some() {
+        404
+    } else if error.downcast_ref::<BadRequestError>().is_some() {
+        400
+    } else if error.downcast_ref::<VetoError>().is_some()
+        || error.downcast_ref::<BlockedError>().is_some()
+    {
+        409
+    } else {
+        500
+    };
+    ApiResponse {
+        status_code,
+        body: Some(json!({ "error": format!("{:#}", error) })),
+    }
+}
+
+fn route(
+    db: &JiraDatabase,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> Result<(u16, Option<Value>)> {
+    let path = url.split('?').next().unwrap_or("");
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["epics"]) => {
+            let db_state = db.read_db()?;
+            let epics: Vec<_> = db_state
+                .get_sorted_epics()
+                .into_iter()
+                .map(|(id, item)| WithId { id, item })
+                .collect();
+            Ok((200, Some(serde_json::to_value(epics)?)))
+        }
+        (Method::Post, ["epics"]) => {
+            let new_item: NewItem = parse_body(body)?;
+            let id = db.create_epic(Epic::new(check_name(new_item.name)?, new_item.description))?;
+            Ok((201, Some(json!({ "id": id }))))
+        }
+        (Method::Get, ["epics", id]) => {
+            let id = parse_id(id)?;
+            let db_state = db.read_db()?;
+            let item = db_state
+                .epics
+                .get(&id)
+                .ok_or(NotFoundError("could not find epic in database!"))?;
+            Ok((200, Some(serde_json::to_value(WithId { id, item })?)))
+        }
+        (Method::Delete, ["epics", id]) => {
+            db.delete_epic(parse_id(id)?)?;
+            Ok((204, None))
+        }
+        (Method::Put, ["epics", id, "status"]) => {
+            let update: StatusUpdate = parse_body(body)?;
+            db.update_epic_status(parse_id(id)?, parse_status(&update.status)?)?;
+            Ok((204, None))
+        }
+        (Method::Get, ["epics", id, "stories"]) => {
+            let id = parse_id(id)?;
+            let db_state = db.read_db()?;
+            let epic = db_state
+                .epics
+                .get(&id)
+                .ok_or(NotFoundError("could not find epic in database!"))?;
+            let stories: Vec<_> = db_state
+                .get_sorted_stories(epic)
+                .into_iter()
+                .map(|(id, item)| WithId { id, item })
+                .collect();
+            Ok((200, Some(serde_json::to_value(stories)?)))
+        }
+        (Method::Post, ["epics", id, "stories"]) => {
+            let new_item: NewItem = parse_body(body)?;
+            let story = Story::new(check_name(new_item.name)?, new_item.description);
+            let id = db.create_story(story, parse_id(id)?)?;
+            Ok((201, Some(json!({ "id": id }))))
+        }
+        (Method::Get, ["stories", id]) => {
+            let id = parse_id(id)?;
+            let db_state = db.read_db()?;
+            let item = db_state
+                .stories
+                .get(&id)
+                .ok_or(NotFoundError("could not find story in database!"))?;
+            Ok((200, Some(serde_json::to_value(WithId { id, item })?)))
+        }
+        (Method::Delete, ["stories", id]) => {
+            let story_id = parse_id(id)?;
+            let epic_id = db
+                .read_db()?
+                .epics
+                .iter()
+                .find(|(_, epic)| epic.stories.contains(&story_id))
+                .map(|(epic_id, _)| *epic_id)
+                .ok_or(NotFoundError("could not find story in database!"))?;
+            db.delete_story(epic_id, story_id)?;
+            Ok((204, None))
+        }
+        (Method::Put, ["stories", id, "status"]) => {
+            let update: StatusUpdate = parse_body(body)?;
+            if update.force {
+                db.force_story_status(parse_id(id)?, parse_status(&update.status)?)?;
+            } else {
+                db.update_story_status(parse_id(id)?, parse_status(&update.status)?)?;
+            }
+            Ok((204, None))
+        }
+        _ => Err(anyhow!(NotFoundError("no such endpoint"))),
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T> {
+    serde_json::from_str(body).map_err(|e| anyhow!(BadRequestError(e.to_string())))
+}
+
+fn parse_id(id: &str) -> Result<u32> {
+    id.parse()
+        .map_err(|_| anyhow!(BadRequestError(format!("invalid id: {}", id))))
+}
+
+fn parse_status(status: &str) -> Result<Status> {
+    status
+        .parse()
+        .map_err(|e: anyhow::Error| anyhow!(BadRequestError(e.to_string())))
+}
+
+fn check_name(name: String) -> Result<String> {
+    let name = name.trim().to_owned();
+    if name.is_empty() {
+        return Err(anyhow!(BadRequestError(
+            "name must not be empty".to_owned()
+        )));
+    }
+    Ok(name)
+}
+
+// binds to localhost unless an address is given, serving until the process is stopped
+pub fn serve(db: &JiraDatabase, address: &str) -> Result<()> {
+    let server = start(address)?;
+    println!("listening on http://{}", server.server_addr());
+    run(&server, db).context("server stopped")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+
+    use super::*;
+    use crate::models::LinkKind;
+
+    // starts a server on an ephemeral port with an empty database, returning its address
+    fn start_test_server() -> (String, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db.json");
+        fs::write(
+            &db_path,
+            r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+        )
+        .unwrap();
+
+        let server = start("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_string();
+        let db_path = db_path.to_str().unwrap().to_owned();
+        thread::spawn(move || {
+            let db = JiraDatabase::new(db_path);
+            run(&server, &db).unwrap();
+        });
+
+        (address, dir)
+    }
+
+    fn send(address: &str, method: &str, path: &str, body: &str) -> (u16, Option<Value>) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status_code = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or("");
+        (status_code, serde_json::from_str(body).ok())
+    }
+
+    #[test]
+    fn server_should_create_read_update_and_delete_items() {
+        let (address, _dir) = start_test_server();
+
+        let (status_code, body) = send(
+            &address,
+            "POST",
+            "/epics",
+            r#"{ "name": "Epic", "description": "All of it" }"#,
+        );
+        assert_eq!(status_code, 201);
+        assert_eq!(body, Some(json!({ "id": 1 })));
+
+        let (status_code, body) = send(
+            &address,
+            "POST",
+            "/epics/1/stories",
+            r#"{ "name": "Story" }"#,
+        );
+        assert_eq!(status_code, 201);
+        assert_eq!(body, Some(json!({ "id": 2 })));
+
+        let (status_code, _) = send(
+            &address,
+            "PUT",
+            "/stories/2/status",
+            r#"{ "status": "in progress" }"#,
+        );
+        assert_eq!(status_code, 204);
+
+        let (status_code, body) = send(&address, "GET", "/epics", "");
+        assert_eq!(status_code, 200);
+        assert_eq!(
+            body,
+            Some(json!([{
+                "id": 1,
+                "name": "Epic",
+                "description": "All of it",
+                "status": "Open",
+                "stories": [2]
+            }]))
+        );
+
+        let (_, body) = send(&address, "GET", "/epics/1/stories", "");
+        assert_eq!(body.unwrap()[0]["status"], "InProgress");
+
+        let (status_code, _) = send(&address, "DELETE", "/stories/2", "");
+        assert_eq!(status_code, 204);
+        let (status_code, _) = send(&address, "GET", "/stories/2", "");
+        assert_eq!(status_code, 404);
+
+        let (status_code, _) = send(&address, "DELETE", "/epics/1", "");
+        assert_eq!(status_code, 204);
+        let (_, body) = send(&address, "GET", "/epics", "");
+        assert_eq!(body, Some(json!([])));
+    }
+
+    #[test]
+    fn server_should_start_blocked_stories_only_when_forced() {
+        let (address, dir) = start_test_server();
+        let db = JiraDatabase::new(dir.path().join("db.json").to_str().unwrap().to_owned());
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_story_link(story_id, LinkKind::BlockedBy, blocker_id)
+            .unwrap();
+        let path = format!("/stories/{}/status", story_id);
+
+        let (status_code, body) = send(&address, "PUT", &path, r#"{ "status": "in progress" }"#);
+        assert_eq!(status_code, 409);
+        assert_eq!(
+            body,
+            Some(json!({ "error": format!("story {} is blocked by {}", story_id, blocker_id) }))
+        );
+        assert_eq!(
+            send(
+                &address,
+                "PUT",
+                &path,
+                r#"{ "status": "in progress", "force": true }"#
+            )
+            .0,
+            204
+        );
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn server_should_map_errors_to_status_codes() {
+        let (address, _dir) = start_test_server();
+
+        let (status_code, body) = send(&address, "GET", "/epics/999", "");
+        assert_eq!(status_code, 404);
+        assert_eq!(
+            body,
+            Some(json!({ "error": "could not find epic in database!" }))
+        );
+
+        assert_eq!(
+            send(
+                &address,
+                "PUT",
+                "/epics/999/status",
+                r#"{ "status": "closed" }"#
+            )
+            .0,
+            404
+        );
+        assert_eq!(
+            send(&address, "POST", "/epics/999/stories", r#"{ "name": "a" }"#).0,
+            404
+        );
+        assert_eq!(send(&address, "GET", "/nothing", "").0, 404);
+        assert_eq!(send(&address, "GET", "/epics/one", "").0, 400);
+        assert_eq!(send(&address, "POST", "/epics", "{").0, 400);
+        assert_eq!(
+            send(&address, "POST", "/epics", r#"{ "name": " " }"#).0,
+            400
+        );
+
+        send(&address, "POST", "/epics", r#"{ "name": "Epic" }"#);
+        assert_eq!(
+            send(
+                &address,
+                "PUT",
+                "/epics/1/status",
+                r#"{ "status": "done" }"#
+            )
+            .0,
+            400
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::Result;
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+use crate::due_date::get_week_start;
+use crate::models::{DBState, Status};
+
+// how many of the largest open epics are listed
+const LARGEST_EPICS: usize = 5;
+// how many weeks of throughput are counted, including the current one
+const THROUGHPUT_WEEKS: u64 = 8;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Stats {
+    pub epics_by_status: BTreeMap<Status, usize>,
+    pub stories_by_status: BTreeMap<Status, usize>,
+    // every epic in id order
+    pub epics: Vec<EpicProgress>,
+    // epics that aren't done with the most open stories first
+    pub largest_open_epics: Vec<EpicProgress>,
+    // days since open stories were created, None when no open story has a logged creation date
+    pub average_open_story_age_days: Option<f64>,
+    // stories moved to RESOLVED or CLOSED each week, oldest week first
+    pub weekly_throughput: Vec<WeekThroughput>,
+    pub average_weekly_throughput: f64,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct EpicProgress {
+    pub id: u32,
+    pub name: String,
+    pub status: Status,
+    pub stories: usize,
+    pub open_stories: usize,
+    // share of the stories that are RESOLVED or CLOSED, 0 for an epic without stories
+    pub percent_complete: f64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct WeekThroughput {
+    // the Monday the week starts on
+    pub week_start: NaiveDate,
+    pub completed: usize,
+}
+
+// Works everything out from the database, ages and throughput come from the status change log
+pub fn get_stats(db_state: &DBState, today: NaiveDate) -> Stats {
+    let mut epics_by_status: BTreeMap<Status, usize> = Status::ALL
+        .iter()
+        .map(|status| (status.clone(), 0))
+        .collect();
+    let mut stories_by_status = epics_by_status.clone();
+    for epic in db_state.epics.values() {
+        *epics_by_status.entry(epic.status.clone()).or_default() += 1;
+    }
+    for story in db_state.stories.values() {
+        *stories_by_status.entry(story.status.clone()).or_default() += 1;
+    }
+
+    let mut epics: Vec<EpicProgress> = db_state
+        .epics
+        .iter()
+        .map(|(id, epic)| {
+            let statuses: Vec<&Status> = epic
+                .stories
+                .iter()
+                .filter_map(|story_id| db_state.stories.get(story_id))
+                .map(|story| &story.status)
+                .collect();
+            let open_stories = statuses.iter().filter(|status| !status.is_done()).count();
+            let percent_complete = if statuses.is_empty() {
+                0.0
+            } else {
+                (statuses.len() - open_stories) as f64 * 100.0 / statuses.len() as f64
+            };
+            EpicProgress {
+                id: *id,
+                name: epic.name.clone(),
+                status: epic.status.clone(),
+                stories: statuses.len(),
+                open_stories,
+                percent_complete,
+            }
+        })
+        .collect();
+    epics.sort_by_key(|epic| epic.id);
+
+    let mut largest_open_epics: Vec<EpicProgress> = epics
+        .iter()
+        .filter(|epic| !epic.status.is_done() && epic.open_stories > 0)
+        .cloned()
+        .collect();
+    largest_open_epics.sort_by_key(|epic| (std::cmp::Reverse(epic.open_stories), epic.id));
+    largest_open_epics.truncate(LARGEST_EPICS);
+
+    let weekly_throughput = get_weekly_throughput(db_state, today);
+    let average_weekly_throughput = weekly_throughput
+        .iter()
+        .map(|week| week.completed)
+        .sum::<usize>() as f64
+        / weekly_throughput.len() as f64;
+
+    Stats {
+        epics_by_status,
+        stories_by_status,
+        epics,
+        largest_open_epics,
+        average_open_story_age_days: get_average_open_story_age(db_state, today),
+        weekly_throughput,
+        average_weekly_throughput,
+    }
+}
+
+fn get_average_open_story_age(db_state: &DBState, today: NaiveDate) -> Option<f64> {
+    let created: HashMap<u32, NaiveDate> = db_state
+        .status_changes
+        .iter()
+        .filter(|change| change.from.is_none())
+        .map(|change| (change.story_id, change.date))
+        .collect();
+    let ages: Vec<i64> = db_state
+        .stories
+        .iter()
+        .filter(|(_, story)| !story.status.is_done())
+        .filter_map(|(id, _)| created.get(id))
+        .map(|date| (today - *date).num_days())
+        .collect();
+    if ages.is_empty() {
+        return None;
+    }
+    Some(ages.iter().sum::<i64>() as f64 / ages.len() as f64)
+}
+
+// Counts the changes from an unfinished status to RESOLVED or CLOSED in each of the last weeks. A
+// story reopened and finished again counts each time
+fn get_weekly_throughput(db_state: &DBState, today: NaiveDate) -> Vec<WeekThroughput> {
+    let this_week = get_week_start(today);
+    let mut weeks: Vec<WeekThroughput> = (0..THROUGHPUT_WEEKS)
+        .rev()
+        .filter_map(|weeks_ago| this_week.checked_sub_days(Days::new(weeks_ago * 7)))
+        .map(|week_start| WeekThroughput {
+            week_start,
+            completed: 0,
+        })
+        .collect();
+
+    for change in &db_state.status_changes {
+        let finished =
+            change.to.is_done() && change.from.as_ref().is_none_or(|from| !from.is_done());
+        if !finished {
+            continue;
+        }
+        if let Some(week) = weeks
+            .iter_mut()
+            .rev()
+            .find(|week| week.week_start <= change.date)
+        {
+            if change.date <= today {
+                week.completed += 1;
+            }
+        }
+    }
+    weeks
+}
+
+// The numbers as plain text for the stats command
+pub fn get_text_summary(stats: &Stats) -> Result<String> {
+    let mut summary = String::new();
+    writeln!(summary, "status        epics  stories")?;
+    for (status, epics) in &stats.epics_by_status {
+        writeln!(
+            summary,
+            "{:<12}  {:>5}  {:>7}",
+            status.to_string(),
+            epics,
+            stats.stories_by_status.get(status).unwrap_or(&0)
+        )?;
+    }
+
+    writeln!(summary, "\nlargest open epics:")?;
+    if stats.largest_open_epics.is_empty() {
+        writeln!(summary, "  none")?;
+    }
+    for epic in &stats.largest_open_epics {
+        writeln!(
+            summary,
+            "  #{} {}: {} open, {:.0}% complete",
+            epic.id, epic.name, epic.open_stories, epic.percent_complete
+        )?;
+    }
+
+    writeln!(summary)?;
+    match stats.average_open_story_age_days {
+        Some(age) => writeln!(summary, "average age of open stories: {:.1} days", age)?,
+        None => writeln!(summary, "average age of open stories: unknown")?,
+    }
+    writeln!(
+        summary,
+        "throughput: {:.1} stories/week over the last {} weeks",
+        stats.average_weekly_throughput,
+        stats.weekly_throughput.len()
+    )?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::date;
+    use crate::models::{Epic, StatusChange, Story};
+
+    // a closed epic, an epic with 3 of 4 stories open and one with a done and an open story
+    fn get_db_state() -> DBState {
+        let mut db_state = DBState::default();
+        let mut closed = Epic::new("Closed".to_owned(), "".to_owned());
+        closed.status = Status::Closed;
+        let closed_id = db_state.add_epic(closed);
+        db_state
+            .add_story(Story::new("".to_owned(), "".to_owned()), closed_id)
+            .unwrap();
+        let big_id = db_state.add_epic(Epic::new("Big".to_owned(), "".to_owned()));
+        let small_id = db_state.add_epic(Epic::new("Small".to_owned(), "".to_owned()));
+        for (epic_id, status) in [
+            (big_id, Status::Open),
+            (big_id, Status::Open),
+            (big_id, Status::InProgress),
+            (big_id, Status::Resolved),
+            (small_id, Status::Closed),
+            (small_id, Status::Open),
+        ] {
+            let mut story = Story::new("".to_owned(), "".to_owned());
+            story.status = status;
+            db_state.add_story(story, epic_id).unwrap();
+        }
+
+        let change = |story_id, date, from, to| StatusChange {
+            story_id,
+            date,
+            from,
+            to,
+        };
+        db_state.status_changes = vec![
+            change(5, date(1, 1), None, Status::Open),
+            change(6, date(1, 11), None, Status::Open),
+            change(8, date(1, 20), Some(Status::Open), Status::Resolved),
+            change(9, date(2, 20), Some(Status::InProgress), Status::Closed),
+            change(9, date(2, 21), Some(Status::Closed), Status::Resolved),
+            change(8, date(2, 22), None, Status::Closed),
+        ];
+        db_state
+    }
+
+    #[test]
+    fn get_stats_should_count_items_and_progress() {
+        let stats = get_stats(&get_db_state(), date(2, 23));
+
+        assert_eq!(
+            stats.stories_by_status.values().collect::<Vec<_>>(),
+            vec![&4, &1, &1, &1]
+        );
+        assert_eq!(
+            stats.epics_by_status.values().collect::<Vec<_>>(),
+            vec![&2, &0, &0, &1]
+        );
+        let progress: Vec<_> = stats
+            .epics
+            .iter()
+            .map(|epic| (epic.id, epic.open_stories, epic.percent_complete))
+            .collect();
+        assert_eq!(progress, vec![(1, 1, 0.0), (3, 3, 25.0), (4, 1, 50.0)]);
+        let largest: Vec<_> = stats
+            .largest_open_epics
+            .iter()
+            .map(|epic| epic.id)
+            .collect();
+        assert_eq!(largest, vec![3, 4]);
+
+        // stories 5 and 6 are open and 53 and 43 days old
+        assert_eq!(stats.average_open_story_age_days, Some(48.0));
+    }
+
+    #[test]
+    fn get_stats_should_count_weekly_throughput() {
+        let stats = get_stats(&get_db_state(), date(2, 23));
+
+        let weeks: Vec<_> = stats
+            .weekly_throughput
+            .iter()
+            .map(|week| (week.week_start, week.completed))
+            .collect();
+        assert_eq!(weeks.len(), 8);
+        assert_eq!(weeks[0], (date(1, 1), 0));
+        assert_eq!(weeks[2], (date(1, 15), 1));
+        assert_eq!(weeks[7], (date(2, 19), 2));
+        assert_eq!(stats.average_weekly_throughput, 3.0 / 8.0);
+    }
+
+    #[test]
+    fn stats_should_serialize_to_json() {
+        let stats = get_stats(&get_db_state(), date(2, 23));
+
+        let json = serde_json::to_value(&stats).unwrap();
+
+        assert_eq!(json["stories_by_status"]["InProgress"], 1);
+        assert_eq!(json["largest_open_epics"][0]["name"], "Big");
+        assert_eq!(json["weekly_throughput"][7]["week_start"], "2024-02-19");
+        assert!(get_text_summary(&stats)
+            .unwrap()
+            .contains("#3 Big: 3 open, 25% complete"));
+    }
+}
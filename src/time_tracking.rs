@@ -0,0 +1,406 @@
+use std::env;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::due_date::get_week_start;
+use crate::models::DBState;
+use crate::ui::get_column_string;
+
+pub const DURATION_HELP: &str = "e.g. 1h30m, 45m, 1.5h or a number of minutes";
+pub const WORK_DATE_HELP: &str = "YYYY-MM-DD, today, yesterday or a weekday";
+
+// column width of the story in the text timesheet
+const STORY_WIDTH: usize = 30;
+
+// The name time is logged under, taken from the environment like git and other tools do
+pub fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.trim().is_empty())
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
+// Reads a duration in minutes from hours and minutes such as "1h30m", "2h", "45m" or "1.5h", or a
+// plain number of minutes. Rounded to the nearest minute, which must be at least one
+pub fn parse_duration(input: &str) -> Result<u32> {
+    let input: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    let error = || anyhow!("invalid duration: {} (use {})", input, DURATION_HELP);
+
+    let minutes = match input.parse::<f64>() {
+        Ok(minutes) => minutes,
+        Err(_) => {
+            let mut minutes = 0.0;
+            let mut rest = input.as_str();
+            while !rest.is_empty() {
+                let unit_index = rest
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .ok_or_else(error)?;
+                let value: f64 = rest[..unit_index].parse().map_err(|_| error())?;
+                let unit = rest[unit_index..].chars().next().ok_or_else(error)?;
+                minutes += match unit {
+                    'h' => value * 60.0,
+                    'm' => value,
+                    _ => return Err(error()),
+                };
+                rest = &rest[unit_index + unit.len_utf8()..];
+            }
+            minutes
+        }
+    };
+
+    let minutes = minutes.round();
+    if !(1.0..=f64::from(u32::MAX)).contains(&minutes) {
+        return Err(error());
+    }
+    Ok(minutes as u32)
+}
+
+// Reads the day time was spent: an ISO date, "today", "yesterday" or a weekday such as "friday",
+// which is the last one before today. Time can't be logged on days that haven't happened yet
+pub fn parse_work_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let error = || anyhow!("invalid date: {} (use {})", input, WORK_DATE_HELP);
+
+    let days_back = match input.as_str() {
+        "today" => Some(0),
+        "yesterday" => Some(1),
+        _ => input.parse::<Weekday>().ok().map(|weekday| {
+            (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday() - 1) % 7
+                + 1
+        }),
+    };
+    let date = match days_back {
+        Some(days) => today
+            .checked_sub_days(Days::new(days.into()))
+            .ok_or_else(error)?,
+        None => NaiveDate::parse_from_str(&input, "%Y-%m-%d").map_err(|_| error())?,
+    };
+    if date > today {
+        return Err(anyhow!("{} is in the future", date));
+    }
+    Ok(date)
+}
+
+// e.g. "1h 30m", "2h" or "45m"
+pub fn format_duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+// The time one user logged on one story in one week
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimesheetRow {
+    pub user: String,
+    // the Monday the week starts on
+    pub week_start: NaiveDate,
+    pub story_id: u32,
+    pub story_name: String,
+    pub epic_id: Option<u32>,
+    // minutes logged each day, Monday first
+    pub days: [u32; 7],
+}
+
+impl TimesheetRow {
+    pub fn total(&self) -> u32 {
+        self.days.iter().sum()
+    }
+}
+
+// Time logged by user and week, only for one user or the week of one date when given. Sorted by
+// week, then user, then story
+pub fn get_timesheet(
+    db_state: &DBState,
+    user: Option<&str>,
+    week: Option<NaiveDate>,
+) -> Vec<TimesheetRow> {
+    let week = week.map(get_week_start);
+    let mut rows: Vec<TimesheetRow> = Vec::new();
+    for worklog in &db_state.worklogs {
+        let week_start = get_week_start(worklog.date);
+        if user.is_some_and(|user| user != worklog.user)
+            || week.is_some_and(|week| week != week_start)
+        {
+            continue;
+        }
+
+        let index = match rows.iter().position(|row| {
+            row.week_start == week_start
+                && row.user == worklog.user
+                && row.story_id == worklog.story_id
+        }) {
+            Some(index) => index,
+            None => {
+                rows.push(TimesheetRow {
+                    user: worklog.user.clone(),
+                    week_start,
+                    story_id: worklog.story_id,
+                    story_name: db_state
+                        .stories
+                        .get(&worklog.story_id)
+                        .map_or_else(|| worklog.story_name.clone(), |story| story.name.clone()),
+                    epic_id: db_state.get_epic_id(worklog.story_id),
+                    days: [0; 7],
+                });
+                rows.len() - 1
+            }
+        };
+        rows[index].days[worklog.date.weekday().num_days_from_monday() as usize] += worklog.minutes;
+    }
+
+    rows.sort_by(|a, b| {
+        (a.week_start, &a.user, a.story_id).cmp(&(b.week_start, &b.user, b.story_id))
+    });
+    rows
+}
+
+// A table of stories by day for each user and week, for the timesheet command
+pub fn get_text_timesheet(rows: &[TimesheetRow]) -> Result<String> {
+    let mut text = String::new();
+    if rows.is_empty() {
+        writeln!(text, "no time logged")?;
+        return Ok(text);
+    }
+
+    for (index, group) in rows
+        .chunk_by(|a, b| a.week_start == b.week_start && a.user == b.user)
+        .enumerate()
+    {
+        if index > 0 {
+            writeln!(text)?;
+        }
+        writeln!(text, "{}, week of {}", group[0].user, group[0].week_start)?;
+        write!(text, "{}", get_column_string("story", STORY_WIDTH))?;
+        for day in ["mon", "tue", "wed", "thu", "fri", "sat", "sun", "total"] {
+            write!(text, "  {:>7}", day)?;
+        }
+        writeln!(text)?;
+
+        for row in group {
+            let story = format!("#{} {}", row.story_id, row.story_name);
+            write_timesheet_line(&mut text, &story, &row.days)?;
+        }
+        let mut totals = [0; 7];
+        for row in group {
+            for (total, minutes) in totals.iter_mut().zip(row.days) {
+                *total += minutes;
+            }
+        }
+        write_timesheet_line(&mut text, "total", &totals)?;
+    }
+    Ok(text)
+}
+
+fn write_timesheet_line(text: &mut String, label: &str, days: &[u32; 7]) -> Result<()> {
+    write!(text, "{}", get_column_string(label, STORY_WIDTH))?;
+    for minutes in days.iter().chain([&days.iter().sum()]) {
+        let cell = if *minutes == 0 {
+            "-".to_owned()
+        } else {
+            format_duration(*minutes)
+        };
+        write!(text, "  {:>7}", cell)?;
+    }
+    writeln!(text)?;
+    Ok(())
+}
+
+// A row per user, week and story with the minutes of each day, for spreadsheets and billing
+pub fn write_timesheet_csv(rows: &[TimesheetRow], writer: impl Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record([
+        "user", "week", "story_id", "epic_id", "story", "mon", "tue", "wed", "thu", "fri", "sat",
+        "sun", "total",
+    ])?;
+    for row in rows {
+        let mut record = vec![
+            row.user.clone(),
+            row.week_start.to_string(),
+            row.story_id.to_string(),
+            row.epic_id.map_or(String::new(), |id| id.to_string()),
+            row.story_name.clone(),
+        ];
+        record.extend(row.days.iter().map(u32::to_string));
+        record.push(row.total().to_string());
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::date;
+    use crate::models::{Epic, Story, Worklog};
+
+    // ann and bob log time on stories 2 and 3 over two weeks
+    fn get_db_state() -> DBState {
+        let mut db_state = DBState::default();
+        let epic_id = db_state.add_epic(Epic::new("".to_owned(), "".to_owned()));
+        for name in ["Login", "Signup"] {
+            db_state
+                .add_story(Story::new(name.to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+        }
+        for (story_id, user, date, minutes) in [
+            (3, "bob", date(1, 30), 60),
+            (2, "ann", date(1, 29), 90),
+            (2, "ann", date(1, 31), 30),
+            (2, "ann", date(1, 29), 15),
+            (3, "ann", date(2, 5), 45),
+        ] {
+            db_state
+                .add_worklog(Worklog {
+                    story_id,
+                    user: user.to_owned(),
+                    date,
+                    minutes,
+                    note: "".to_owned(),
+                    story_name: "".to_owned(),
+                })
+                .unwrap();
+        }
+        db_state
+    }
+
+    #[test]
+    fn parse_duration_should_accept_hours_and_minutes() {
+        let cases = [
+            ("90", 90),
+            ("45m", 45),
+            ("2h", 120),
+            ("1h30m", 90),
+            ("1h 15m", 75),
+            ("1.5H", 90),
+            ("0.25h", 15),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_duration(input).unwrap(), expected, "{}", input);
+        }
+
+        for input in ["", "0", "0m", "h", "1h30", "2d", "-5m", "1..5h", "1hé"] {
+            assert!(parse_duration(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_work_date_should_accept_past_dates() {
+        // a Wednesday
+        let today = date(1, 31);
+
+        let cases = [
+            ("2024-01-02", date(1, 2)),
+            ("today", today),
+            ("Yesterday", date(1, 30)),
+            ("monday", date(1, 29)),
+            ("wed", date(1, 24)),
+            ("thursday", date(1, 25)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_work_date(input, today).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+
+        for input in ["2024-02-01", "tomorrow", "+1d", "someday", ""] {
+            assert!(parse_work_date(input, today).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn format_duration_should_show_hours_and_minutes() {
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(135), "2h 15m");
+    }
+
+    #[test]
+    fn get_timesheet_should_group_by_week_user_and_story() {
+        let db_state = get_db_state();
+
+        let rows = get_timesheet(&db_state, None, None);
+
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|row| (row.week_start, row.user.as_str(), row.story_id, row.total()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (date(1, 29), "ann", 2, 135),
+                (date(1, 29), "bob", 3, 60),
+                (date(2, 5), "ann", 3, 45),
+            ]
+        );
+        assert_eq!(rows[0].days, [105, 0, 30, 0, 0, 0, 0]);
+        assert_eq!(rows[0].epic_id, Some(1));
+        assert_eq!(rows[0].story_name, "Login");
+
+        assert_eq!(get_timesheet(&db_state, Some("bob"), None).len(), 1);
+        assert_eq!(
+            get_timesheet(&db_state, Some("ann"), Some(date(2, 7))).len(),
+            1
+        );
+        assert!(get_timesheet(&db_state, Some("carol"), None).is_empty());
+
+        // time logged on a deleted story is still listed under its name
+        let mut db_state = db_state;
+        db_state
+            .epics
+            .get_mut(&1)
+            .unwrap()
+            .stories
+            .retain(|id| *id != 2);
+        db_state.remove_story(2).unwrap();
+        let rows = get_timesheet(&db_state, Some("ann"), Some(date(1, 29)));
+        assert_eq!(rows[0].story_name, "Login");
+        assert_eq!(rows[0].epic_id, None);
+    }
+
+    #[test]
+    fn timesheet_should_be_written_as_text_and_csv() {
+        let rows = get_timesheet(&get_db_state(), Some("ann"), Some(date(1, 29)));
+
+        let text = get_text_timesheet(&rows).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "ann, week of 2024-01-29");
+        assert_eq!(
+            lines[2],
+            "#2 Login                         1h 45m        -      30m        -        -        -        -   2h 15m"
+        );
+        assert!(lines[3].starts_with("total"));
+        assert_eq!(get_text_timesheet(&[]).unwrap(), "no time logged\n");
+
+        let mut csv = Vec::new();
+        write_timesheet_csv(&rows, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "user,week,story_id,epic_id,story,mon,tue,wed,thu,fri,sat,sun,total\n\
+             ann,2024-01-29,2,1,Login,105,0,30,0,0,0,0,135\n"
+        );
+
+        // wide characters take two columns each
+        let mut rows = rows;
+        rows[0].story_name = "ログイン画面".to_owned();
+        let text = get_text_timesheet(&rows).unwrap();
+        assert!(text
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with(&format!("#2 ログイン画面{}   1h 45m", " ".repeat(15))));
+    }
+}
@@ -0,0 +1,187 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::io_utils::get_user_input;
+use crate::ui::{get_theme, Page};
+
+// What the main loop should do with a key press in raw mode
+#[derive(Debug, PartialEq, Eq)]
+pub enum RawInput {
+    // pass to the current page's handle_input(), just like a line typed in line mode
+    Command(String),
+    // nothing to hand to the page, e.g. the highlight moved or the terminal was resized
+    Redraw,
+    Quit,
+}
+
+// Raw mode needs a real terminal on both ends. Dumb terminals and piped input or output use the
+// line-based mode instead
+pub fn is_supported() -> bool {
+    io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+pub fn enter() -> Result<()> {
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    Ok(())
+}
+
+pub fn leave() -> Result<()> {
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, cursor::Show)?;
+    Ok(())
+}
+
+pub fn clear_screen() -> Result<()> {
+    execute!(
+        io::stdout(),
+        terminal::Clear(ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    Ok(())
+}
+
+// Waits for a single key press and maps it to an input for the page. Raw mode is only enabled
+// while waiting so pages and prompts can keep printing with println!()
+pub fn read_input(page: &dyn Page) -> Result<RawInput> {
+    // takes the place of the input line so the page layout fits the same as in line mode
+    let theme = get_theme();
+    print!(
+        "{}",
+        theme.paint(
+            "[up/down or k/j] select | [enter] open | [:] type a command",
+            &theme.command_bar
+        )
+    );
+    io::stdout().flush()?;
+
+    terminal::enable_raw_mode()?;
+    let event = event::read();
+    terminal::disable_raw_mode()?;
+
+    match event? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => map_key(page, key),
+        _ => Ok(RawInput::Redraw),
+    }
+}
+
+fn map_key(page: &dyn Page, key: KeyEvent) -> Result<RawInput> {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Ok(match key.code {
+            KeyCode::Char('c') | KeyCode::Char('d') => RawInput::Quit,
+            _ => RawInput::Redraw,
+        });
+    }
+
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => {
+            page.move_selection(-1);
+            Ok(RawInput::Redraw)
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            page.move_selection(1);
+            Ok(RawInput::Redraw)
+        }
+        KeyCode::Left => Ok(RawInput::Command("<".to_owned())),
+        KeyCode::Right => Ok(RawInput::Command(">".to_owned())),
+        KeyCode::Enter => Ok(page
+            .get_selected_input()?
+            .map_or(RawInput::Redraw, RawInput::Command)),
+        // commands longer than one key, such as ids, are typed on a line of their own
+        KeyCode::Char(':') => Ok(read_command_line("")),
+        KeyCode::Char(c) if c.is_ascii_digit() => Ok(read_command_line(&c.to_string())),
+        KeyCode::Char(c) => Ok(RawInput::Command(c.to_string())),
+        _ => Ok(RawInput::Redraw),
+    }
+}
+
+fn read_command_line(typed: &str) -> RawInput {
+    print!("\n:{}", typed);
+    let _ = io::stdout().flush();
+    RawInput::Command(format!("{}{}", typed, get_user_input().trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::db::{test_utils::MockDB, JiraDatabase};
+    use crate::models::Epic;
+    use crate::ui::HomePage;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn map_key_should_return_the_correct_inputs() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let first_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let second_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let page = HomePage::new(db);
+        page.draw_page().unwrap();
+
+        // nothing is highlighted until the selection is moved
+        assert_eq!(
+            map_key(&page, key(KeyCode::Enter)).unwrap(),
+            RawInput::Redraw
+        );
+
+        page.move_selection(0);
+        page.draw_page().unwrap();
+        assert_eq!(
+            map_key(&page, key(KeyCode::Enter)).unwrap(),
+            RawInput::Command(first_id.to_string())
+        );
+
+        assert_eq!(
+            map_key(&page, key(KeyCode::Char('j'))).unwrap(),
+            RawInput::Redraw
+        );
+        page.draw_page().unwrap();
+        assert_eq!(
+            map_key(&page, key(KeyCode::Enter)).unwrap(),
+            RawInput::Command(second_id.to_string())
+        );
+
+        assert_eq!(map_key(&page, key(KeyCode::Up)).unwrap(), RawInput::Redraw);
+        page.draw_page().unwrap();
+        assert_eq!(
+            map_key(&page, key(KeyCode::Enter)).unwrap(),
+            RawInput::Command(first_id.to_string())
+        );
+
+        assert_eq!(
+            map_key(&page, key(KeyCode::Char('c'))).unwrap(),
+            RawInput::Command("c".to_owned())
+        );
+        assert_eq!(
+            map_key(&page, key(KeyCode::Right)).unwrap(),
+            RawInput::Command(">".to_owned())
+        );
+        assert_eq!(
+            map_key(
+                &page,
+                KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+            )
+            .unwrap(),
+            RawInput::Quit
+        );
+        assert_eq!(map_key(&page, key(KeyCode::Tab)).unwrap(), RawInput::Redraw);
+    }
+}
@@ -0,0 +1,273 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+use crate::models::{Epic, Status, Story};
+
+const FRONT_MATTER_DELIMITER: &str = "---";
+// lines starting with this before the front matter are ignored, used to show parse errors
+const COMMENT_PREFIX: &str = "#";
+
+// The editable fields of an epic or story, written as a front matter header holding the name and
+// status followed by the description as the body:
+//
+// ---
+// name: Banana
+// status: OPEN
+// ---
+// Consume the banana
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ItemText {
+    pub name: String,
+    pub status: Status,
+    pub description: String,
+}
+
+impl ItemText {
+    pub fn from_epic(epic: &Epic) -> Self {
+        Self {
+            name: epic.name.clone(),
+            status: epic.status.clone(),
+            description: epic.description.clone(),
+        }
+    }
+
+    pub fn from_story(story: &Story) -> Self {
+        Self {
+            name: story.name.clone(),
+            status: story.status.clone(),
+            description: story.description.clone(),
+        }
+    }
+
+    // fields not in the text, such as the epic's stories, are kept from the original
+    pub fn apply_to_epic(self, mut epic: Epic) -> Epic {
+        epic.name = self.name;
+        epic.status = self.status;
+        epic.description = self.description;
+        epic
+    }
+
+    pub fn apply_to_story(self, mut story: Story) -> Story {
+        story.name = self.name;
+        story.status = self.status;
+        story.description = self.description;
+        story
+    }
+
+    pub fn format(&self) -> String {
+        format!(
+            "{delimiter}\nname: {}\nstatus: {}\n{delimiter}\n{}\n",
+            self.name,
+            self.status,
+            self.description,
+            delimiter = FRONT_MATTER_DELIMITER
+        )
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text
+            .lines()
+            .skip_while(|line| line.starts_with(COMMENT_PREFIX) || line.trim().is_empty());
+
+        if lines.next().map(str::trim) != Some(FRONT_MATTER_DELIMITER) {
+            return Err(anyhow!(
+                "the item must start with a '{}' line",
+                FRONT_MATTER_DELIMITER
+            ));
+        }
+
+        let mut name = None;
+        let mut status = None;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim() == FRONT_MATTER_DELIMITER {
+                closed = true;
+                break;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| anyhow!("expected 'field: value' but found '{}'", line))?;
+            let value = value.trim().to_owned();
+            match key.trim() {
+                "name" if name.is_none() => name = Some(value),
+                "status" if status.is_none() => status = Some(value.parse::<Status>()?),
+                "name" | "status" => return Err(anyhow!("'{}' is set more than once", key.trim())),
+                key => return Err(anyhow!("unknown field '{}'", key)),
+            }
+        }
+        if !closed {
+            return Err(anyhow!(
+                "the header must end with a '{}' line",
+                FRONT_MATTER_DELIMITER
+            ));
+        }
+
+        let name = name
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("name must not be empty"))?;
+        let status = status.ok_or_else(|| anyhow!("status is missing"))?;
+        let description = lines.collect::<Vec<_>>().join("\n").trim().to_owned();
+
+        Ok(Self {
+            name,
+            status,
+            description,
+        })
+    }
+}
+
+// the editor command from $VISUAL or $EDITOR, falling back to vi
+pub fn get_editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_owned())
+}
+
+// Opens the item in the editor until it parses. Parse errors are written as comments above the
+// item before the editor is reopened. Returns None when the file is emptied, which cancels the
+// edit, and gives up with the parse error if the file is saved again without changes
+pub fn edit_item(editor: &str, item: &ItemText) -> Result<Option<ItemText>> {
+    let mut text = item.format();
+    loop {
+        let edited = run_editor(editor, &text)?;
+        let content: String = edited
+            .lines()
+            .skip_while(|line| line.starts_with(COMMENT_PREFIX))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match ItemText::parse(&content) {
+            Ok(item) => return Ok(Some(item)),
+            Err(e) if edited == text => return Err(e),
+            Err(e) => {
+                text = format!(
+                    "{prefix} ERROR: {}\n{prefix} Fix the item below and save again, or delete everything to cancel\n{}",
+                    e,
+                    content,
+                    prefix = COMMENT_PREFIX
+                );
+            }
+        }
+    }
+}
+
+// writes the text to a temp file, waits for the editor to exit and returns the saved text. The
+// editor command may include arguments e.g. "code --wait"
+fn run_editor(editor: &str, text: &str) -> Result<String> {
+    let file = tempfile::Builder::new().suffix(".md").tempfile()?;
+    fs::write(file.path(), text)?;
+
+    let mut args = editor.split_whitespace();
+    let program = args.next().ok_or_else(|| anyhow!("no editor set"))?;
+    let status = Command::new(program)
+        .args(args)
+        .arg(file.path())
+        .status()
+        .map_err(|e| anyhow!("failed to launch editor '{}': {}", editor, e))?;
+    if !status.success() {
+        return Err(anyhow!("editor exited with {}", status));
+    }
+
+    Ok(fs::read_to_string(file.path())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_item() -> ItemText {
+        ItemText {
+            name: "Banana".to_owned(),
+            status: Status::InProgress,
+            description: "Consume the banana\n\nThen the next one".to_owned(),
+        }
+    }
+
+    #[test]
+    fn format_should_write_front_matter() {
+        assert_eq!(
+            get_item().format(),
+            "---\nname: Banana\nstatus: IN PROGRESS\n---\nConsume the banana\n\nThen the next one\n"
+        );
+    }
+
+    #[test]
+    fn parse_should_read_formatted_item() {
+        let item = get_item();
+        assert_eq!(ItemText::parse(&item.format()).unwrap(), item);
+
+        let text = "# ERROR: old error\n\n---\n name : Get it: now \n\nstatus: closed\n---\n\nSource it\n\n";
+        assert_eq!(
+            ItemText::parse(text).unwrap(),
+            ItemText {
+                name: "Get it: now".to_owned(),
+                status: Status::Closed,
+                description: "Source it".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_should_fail_for_invalid_items() {
+        let invalid = [
+            "name: Banana\nstatus: OPEN\n",
+            "---\nname: Banana\nstatus: OPEN\n",
+            "---\nname: \nstatus: OPEN\n---\n",
+            "---\nstatus: OPEN\n---\n",
+            "---\nname: Banana\n---\n",
+            "---\nname: Banana\nstatus: DONE\n---\n",
+            "---\nname: Banana\nname: Apple\nstatus: OPEN\n---\n",
+            "---\nname: Banana\nstatus: OPEN\nowner: me\n---\n",
+            "---\nname Banana\nstatus: OPEN\n---\n",
+        ];
+        for text in invalid {
+            assert!(ItemText::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn apply_should_keep_other_fields() {
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.stories = vec![2, 3];
+
+        let epic = get_item().apply_to_epic(epic);
+        assert_eq!(epic.name, "Banana");
+        assert_eq!(epic.status, Status::InProgress);
+        assert_eq!(epic.stories, vec![2, 3]);
+    }
+
+    #[test]
+    fn edit_item_should_return_edited_item() {
+        let edited = edit_item("sed -i s/Banana/Apple/", &get_item()).unwrap();
+        assert_eq!(edited.unwrap().name, "Apple");
+
+        let unchanged = edit_item("true", &get_item()).unwrap();
+        assert_eq!(unchanged, Some(get_item()));
+    }
+
+    #[test]
+    fn edit_item_should_cancel_when_emptied() {
+        assert_eq!(edit_item("truncate -s 0", &get_item()).unwrap(), None);
+    }
+
+    #[test]
+    fn edit_item_should_reopen_editor_on_errors() {
+        // the first save breaks the status, the second sees the error comment and gives up
+        // without changing anything
+        let result = edit_item("sed -i s/IN.PROGRESS/DONE/", &get_item());
+        assert!(result.unwrap_err().to_string().contains("DONE"));
+
+        assert!(edit_item("false", &get_item()).is_err());
+    }
+}
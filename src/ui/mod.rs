@@ -1,5 +1,8 @@
+mod editor;
 mod pages;
 mod prompts;
+mod theme;
 
 pub use pages::*;
 pub use prompts::*;
+pub use theme::*;
@@ -1,48 +1,229 @@
 use std::any::Any;
+use std::cell::Cell;
 use std::rc::Rc;
 
 use anyhow::anyhow;
 use anyhow::Result;
-use clearscreen;
+use chrono::{Local, NaiveDate};
 use itertools::Itertools;
 
+use crate::charts::{draw_chart, get_default_range, get_series, ChartKind};
 use crate::db::JiraDatabase;
-use crate::models::Action;
+use crate::due_date::{get_days_late, today};
+use crate::models::{
+    Action, ChartScope, DBState, ListItem, ListPage, SortKey, SortOrder, Sprint, Status, Story,
+    Worklog,
+};
+use crate::stats::get_stats;
+use crate::time_tracking::{current_user, format_duration};
+use crate::ui::get_theme;
 
 mod page_helpers;
 use page_helpers::*;
+pub use page_helpers::{get_column_string, get_terminal_width};
+
+mod table;
+use table::*;
 
 pub trait Page {
     fn draw_page(&self) -> Result<()>;
     fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+    // Used by the raw-mode TUI. Moves the highlighted row by offset, an offset of 0 turns the
+    // highlight on. Pages without selectable rows ignore it
+    fn move_selection(&self, _offset: isize) {}
+    // the input that opens the highlighted row, passed to handle_input() when Enter is pressed
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
     // as_any() has been added to all page objects. Used to support down-casting, which is used in
     // Navigator tests. For more info check out this StackOverflow post
     // https://stackoverflow.com/questions/33687447/how-to-get-a-reference-to-a-concrete-type-from-a-trait-object
+    #[allow(dead_code)]
     fn as_any(&self) -> &dyn Any;
 }
 
+// Every project, the root page. Picking one scopes the other pages to its epics
+pub struct Projects {
+    pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl Projects {
+    // lines printed around the table: 2 header lines, 4 footer lines and the input line
+    const RESERVED_LINES: usize = 7;
+
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            pager: Pager::default(),
+        }
+    }
+}
+
+impl Page for Projects {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let projects = db_state.get_sorted_projects();
+
+        let table = Table::new(vec![
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("key", 5, 10, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new("epics", 5, 5, 0),
+        ]);
+        println!("{}", table.get_title_string("PROJECTS"));
+        println!("{}", table.get_header_string());
+
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        for (row, (id, project)) in self.pager.get_page(&projects, page_size).iter().enumerate() {
+            let row_string = table.get_row_string(&[
+                &id.to_string(),
+                &project.key,
+                &project.name,
+                &project.epics.len().to_string(),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
+        }
+
+        println!("\n{}", self.pager.get_footer(projects.len(), page_size));
+        let unassigned = db_state
+            .epics
+            .keys()
+            .filter(|id| db_state.get_project_id(**id).is_none())
+            .count();
+        println!("epics in no project: {}", unassigned);
+        println!(
+            "{}",
+            get_command_bar_string(
+                "[q] quit | [c] create project | [a] all epics | [>] next page | [<] previous page | [:id:] navigate to project"
+            )
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        match input {
+            "q" => Ok(Some(Action::Exit)),
+            "c" => Ok(Some(Action::CreateProject)),
+            "a" => Ok(Some(Action::NavigateToProject { project_id: None })),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
+            input => {
+                if let Ok(valid_int) = input.parse::<u32>() {
+                    let projects = self.db.read_db()?.projects;
+                    if projects.contains_key(&valid_int) {
+                        return Ok(Some(Action::NavigateToProject {
+                            project_id: Some(valid_int),
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let projects = db_state.get_sorted_projects();
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| projects.get(index))
+            .map(|(id, _)| id.to_string()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 pub struct HomePage {
     pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl HomePage {
+    // lines printed around the epics table: 2 header lines, 3 footer lines and the input line
+    const RESERVED_LINES: usize = 6;
+
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            pager: Pager::default(),
+        }
+    }
+
+    fn get_table(sort_order: &SortOrder) -> Table {
+        Table::new(vec![
+            ColumnSpec::new(get_sortable_header("id", SortKey::Id, sort_order), 4, 12, 1),
+            ColumnSpec::new(
+                get_sortable_header("name", SortKey::Name, sort_order),
+                10,
+                usize::MAX,
+                4,
+            ),
+            ColumnSpec::new(
+                get_sortable_header("status", SortKey::Status, sort_order),
+                11,
+                17,
+                1,
+            ),
+            ColumnSpec::new("due", 11, 11, 0),
+        ])
+    }
 }
 
 impl Page for HomePage {
     fn draw_page(&self) -> Result<()> {
-        println!("----------------------------- EPICS -----------------------------");
-        println!("     id     |               name               |      status     ");
+        let db_state = self.db.read_db()?;
+        let sort_order = db_state.get_sort_order(ListPage::Home);
+
+        let table = Self::get_table(&sort_order);
 
-        let epics = self.db.read_db()?.epics;
-        let sorted_keys = epics.keys().sorted();
-        sorted_keys.for_each(|key| {
-            let epic = &epics[key];
+        let title = match self
+            .db
+            .get_current_project()
+            .and_then(|id| db_state.projects.get(&id))
+        {
+            Some(project) => format!("{} EPICS", project.key),
+            None => "EPICS".to_owned(),
+        };
+        println!("{}", table.get_title_string(&title));
+        println!("{}", table.get_header_string());
+
+        let epics = db_state.get_sorted_epics();
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        let today = today();
+        for (row, (id, epic)) in self.pager.get_page(&epics, page_size).iter().enumerate() {
+            let row_string = table.get_row_string(&[
+                &id.to_string(),
+                &epic.name,
+                &get_theme().paint_status(&epic.status),
+                &get_theme().paint_due_date(*epic, today),
+            ]);
             println!(
-                "{}|{}|{}",
-                get_column_string(&key.to_string(), 12),
-                get_column_string(&epic.name, 34),
-                get_column_string(&epic.status.to_string(), 17)
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
             );
-        });
+        }
 
-        println!("\n[q] quit | [c] create epic | [:id:] navigate to epic");
+        println!("\n{}", self.pager.get_footer(epics.len(), page_size));
+        println!("{}", get_command_bar_string("[q] quit | [p] previous | [c] create epic | [C] create epic in editor | [b] board | [o] overdue | [S] sprints | [g] burndown | [D] dashboard | [s] change sort | [r] reverse sort | [>] next page | [<] previous page | [:id:] navigate to epic"));
 
         Ok(())
     }
@@ -51,7 +232,30 @@ impl Page for HomePage {
         // match against the user input and return the corresponding action. If the user input was invalid return None.
         match input {
             "q" => Ok(Some(Action::Exit)),
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
             "c" => Ok(Some(Action::CreateEpic)),
+            "C" => Ok(Some(Action::CreateEpicInEditor)),
+            "b" => Ok(Some(Action::NavigateToBoard { epic_id: None })),
+            "o" => Ok(Some(Action::NavigateToOverdue)),
+            "S" => Ok(Some(Action::NavigateToSprints)),
+            "g" => Ok(Some(Action::NavigateToChart {
+                scope: ChartScope::All,
+            })),
+            "D" => Ok(Some(Action::NavigateToStats)),
+            "s" => Ok(Some(Action::CycleSortKey {
+                page: ListPage::Home,
+            })),
+            "r" => Ok(Some(Action::ToggleSortDirection {
+                page: ListPage::Home,
+            })),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
             input => {
                 if let Ok(valid_int) = input.parse::<u32>() {
                     let epics = self.db.read_db()?.epics;
@@ -64,6 +268,20 @@ impl Page for HomePage {
         }
     }
 
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let epics = db_state.get_sorted_epics();
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| epics.get(index))
+            .map(|(id, _)| id.to_string()))
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -72,6 +290,26 @@ impl Page for HomePage {
 pub struct EpicDetail {
     pub epic_id: u32,
     pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl EpicDetail {
+    // lines printed around the stories table: 8 lines of epic details and story headers, 6 footer
+    // lines and the input line
+    const RESERVED_LINES: usize = 15;
+
+    pub fn new(epic_id: u32, db: Rc<JiraDatabase>) -> Self {
+        Self {
+            epic_id,
+            db,
+            pager: Pager::default(),
+        }
+    }
+
+    fn get_stories_table(sort_order: &SortOrder) -> Table {
+        // same layout as the epics table on the HomePage
+        HomePage::get_table(sort_order)
+    }
 }
 
 impl Page for EpicDetail {
@@ -82,34 +320,50 @@ impl Page for EpicDetail {
             .get(&self.epic_id)
             .ok_or_else(|| anyhow!("could not find epic!"))?;
 
-        println!("------------------------------ EPIC ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
+        let table = get_item_details_table();
+        println!("{}", table.get_title_string("EPIC"));
+        println!("{}", table.get_header_string());
         println!(
-            "{}|{}|{}|{}",
-            get_column_string(&self.epic_id.to_string(), 6),
-            get_column_string(&epic.name, 14),
-            get_column_string(&epic.description, 29),
-            get_column_string(&epic.status.to_string(), 14),
+            "{}",
+            table.get_row_string(&[
+                &self.epic_id.to_string(),
+                &epic.name,
+                &epic.description,
+                &get_theme().paint_status(&epic.status),
+                &get_theme().paint_due_date(epic, today()),
+            ])
         );
 
-        println!("\n\n");
-        println!("---------------------------- STORIES ----------------------------");
-        println!("     id     |               name               |      status      ");
+        println!(
+            "\ntime logged: {}\n",
+            format_duration(db_state.get_epic_minutes(self.epic_id))
+        );
+        let sort_order = db_state.get_sort_order(ListPage::EpicDetail);
+        let table = Self::get_stories_table(&sort_order);
 
-        let stories = &db_state.stories;
-        for key in epic.stories.iter().sorted() {
-            if let Some(story) = stories.get(key) {
-                println!(
-                    "{}|{}|{}",
-                    get_column_string(&key.to_string(), 12),
-                    get_column_string(&story.name, 34),
-                    get_column_string(&story.status.to_string(), 17),
-                );
-            }
+        println!("{}", table.get_title_string("STORIES"));
+        println!("{}", table.get_header_string());
+
+        let stories = db_state.get_sorted_stories(epic);
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        let today = today();
+        for (row, (id, story)) in self.pager.get_page(&stories, page_size).iter().enumerate() {
+            let blocked = db_state.is_blocked(*id);
+            let row_string = table.get_row_string(&[
+                &id.to_string(),
+                &get_theme().paint_blocked(&story.name, blocked),
+                &get_theme().paint_status(&story.status),
+                &get_theme().paint_due_date(*story, today),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
         }
 
+        println!("\n{}", self.pager.get_footer(stories.len(), page_size));
         println!("\n\n");
-        println!("[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story");
+        println!("{}", get_command_bar_string("[p] previous | [u] update epic | [e] edit epic | [t] set due date | [m] move to project | [d] delete epic | [c] create story | [C] create story in editor | [b] board | [g] burndown | [s] change sort | [r] reverse sort | [>] next page | [<] previous page | [:id:] navigate to story"));
 
         Ok(())
     }
@@ -121,12 +375,44 @@ impl Page for EpicDetail {
             "u" => Ok(Some(Action::UpdateEpicStatus {
                 epic_id: self.epic_id,
             })),
+            "e" => Ok(Some(Action::EditEpic {
+                epic_id: self.epic_id,
+            })),
+            "t" => Ok(Some(Action::UpdateEpicDueDate {
+                epic_id: self.epic_id,
+            })),
+            "m" => Ok(Some(Action::MoveEpic {
+                epic_id: self.epic_id,
+            })),
             "d" => Ok(Some(Action::DeleteEpic {
                 epic_id: self.epic_id,
             })),
             "c" => Ok(Some(Action::CreateStory {
                 epic_id: self.epic_id,
             })),
+            "C" => Ok(Some(Action::CreateStoryInEditor {
+                epic_id: self.epic_id,
+            })),
+            "b" => Ok(Some(Action::NavigateToBoard {
+                epic_id: Some(self.epic_id),
+            })),
+            "g" => Ok(Some(Action::NavigateToChart {
+                scope: ChartScope::Epic(self.epic_id),
+            })),
+            "s" => Ok(Some(Action::CycleSortKey {
+                page: ListPage::EpicDetail,
+            })),
+            "r" => Ok(Some(Action::ToggleSortDirection {
+                page: ListPage::EpicDetail,
+            })),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
             input => {
                 if let Ok(valid_int) = input.parse::<u32>() {
                     let stories = self.db.read_db()?.stories;
@@ -142,11 +428,70 @@ impl Page for EpicDetail {
         }
     }
 
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let epic = db_state
+            .epics
+            .get(&self.epic_id)
+            .ok_or_else(|| anyhow!("could not find epic!"))?;
+        let stories = db_state.get_sorted_stories(epic);
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| stories.get(index))
+            .map(|(id, _)| id.to_string()))
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+// id, name, description and status of a single epic or story, as shown on the detail pages
+fn get_item_details_table() -> Table {
+    Table::new(vec![
+        ColumnSpec::new("id", 4, 8, 1),
+        ColumnSpec::new("name", 10, 40, 2),
+        ColumnSpec::new("description", 11, usize::MAX, 4),
+        ColumnSpec::new("status", 11, 15, 0),
+        ColumnSpec::new("due", 11, 11, 0),
+    ])
+}
+
+// commits linked to a story, with the short hash and the day they were authored
+fn get_commits_table() -> Table {
+    Table::new(vec![
+        ColumnSpec::new("commit", 8, 8, 0),
+        ColumnSpec::new("date", 10, 10, 0),
+        ColumnSpec::new("author", 6, 20, 1),
+        ColumnSpec::new("subject", 7, usize::MAX, 4),
+    ])
+}
+
+// the stories a story is linked to
+fn get_links_table() -> Table {
+    Table::new(vec![
+        ColumnSpec::new("link", 10, 10, 0),
+        ColumnSpec::new("id", 4, 8, 1),
+        ColumnSpec::new("name", 10, usize::MAX, 4),
+        ColumnSpec::new("status", 11, 15, 0),
+    ])
+}
+
+// time logged on a story, oldest first
+fn get_worklogs_table() -> Table {
+    Table::new(vec![
+        ColumnSpec::new("date", 10, 10, 0),
+        ColumnSpec::new("user", 4, 20, 1),
+        ColumnSpec::new("time", 7, 8, 0),
+        ColumnSpec::new("note", 4, usize::MAX, 4),
+    ])
+}
+
 pub struct StoryDetail {
     pub epic_id: u32,
     pub story_id: u32,
@@ -161,19 +506,107 @@ impl Page for StoryDetail {
             .get(&self.story_id)
             .ok_or_else(|| anyhow!("could not find story!"))?;
 
-        println!("------------------------------ STORY ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
+        let table = get_item_details_table();
+        println!("{}", table.get_title_string("STORY"));
+        println!("{}", table.get_header_string());
 
+        let blocked = db_state.is_blocked(self.story_id);
         println!(
-            "{}|{}|{}|{}",
-            get_column_string(&self.story_id.to_string(), 6),
-            get_column_string(&story.name, 14),
-            get_column_string(&story.description, 29),
-            get_column_string(&story.status.to_string(), 15),
+            "{}",
+            table.get_row_string(&[
+                &self.story_id.to_string(),
+                &get_theme().paint_blocked(&story.name, blocked),
+                &story.description,
+                &get_theme().paint_status(&story.status),
+                &get_theme().paint_due_date(story, today()),
+            ])
         );
 
+        if !story.commits.is_empty() {
+            let table = get_commits_table();
+            println!("\n{}", table.get_title_string("COMMITS"));
+            println!("{}", table.get_header_string());
+            for commit in &story.commits {
+                println!(
+                    "{}",
+                    table.get_row_string(&[
+                        commit.hash.get(..8).unwrap_or(&commit.hash),
+                        commit.date.get(..10).unwrap_or(&commit.date),
+                        &commit.author,
+                        &commit.subject,
+                    ])
+                );
+            }
+        }
+
+        let links = db_state.get_story_links(self.story_id);
+        if !links.is_empty() {
+            let table = get_links_table();
+            println!("\n{}", table.get_title_string("LINKS"));
+            println!("{}", table.get_header_string());
+            for (kind, id) in links {
+                let (name, status) = db_state
+                    .stories
+                    .get(&id)
+                    .map_or((String::new(), String::new()), |story| {
+                        (story.name.clone(), get_theme().paint_status(&story.status))
+                    });
+                println!(
+                    "{}",
+                    table.get_row_string(&[&kind.to_string(), &id.to_string(), &name, &status])
+                );
+            }
+        }
+
+        let worklogs: Vec<&Worklog> = db_state
+            .worklogs
+            .iter()
+            .filter(|worklog| worklog.story_id == self.story_id)
+            .collect();
+        if !worklogs.is_empty() {
+            let table = get_worklogs_table();
+            println!("\n{}", table.get_title_string("WORKLOG"));
+            println!("{}", table.get_header_string());
+            for worklog in worklogs {
+                println!(
+                    "{}",
+                    table.get_row_string(&[
+                        &worklog.date.to_string(),
+                        &worklog.user,
+                        &format_duration(worklog.minutes),
+                        &worklog.note,
+                    ])
+                );
+            }
+        }
+
+        println!(
+            "\ntime logged: {}",
+            format_duration(db_state.get_story_minutes(self.story_id))
+        );
+        let timer = db_state.get_timer(self.story_id, &current_user());
+        if let Some(timer) = timer {
+            let minutes = (Local::now() - timer.started_at).num_minutes().max(0);
+            println!(
+                "timer running since {} ({})",
+                timer.started_at.format("%Y-%m-%d %H:%M"),
+                format_duration(u32::try_from(minutes).unwrap_or(u32::MAX))
+            );
+        }
+
         println!("\n\n");
-        println!("[p] previous | [u] update story | [d] delete story");
+        let timer_command = if timer.is_some() {
+            "[x] stop timer"
+        } else {
+            "[s] start timer"
+        };
+        println!(
+            "{}",
+            get_command_bar_string(&format!(
+                "[p] previous | [u] update story | [e] edit story | [t] set due date | {} | [l] log work | [a] add link | [r] remove link | [d] delete story",
+                timer_command
+            ))
+        );
 
         Ok(())
     }
@@ -185,10 +618,717 @@ impl Page for StoryDetail {
             "u" => Ok(Some(Action::UpdateStoryStatus {
                 story_id: self.story_id,
             })),
+            "e" => Ok(Some(Action::EditStory {
+                story_id: self.story_id,
+            })),
+            "t" => Ok(Some(Action::UpdateStoryDueDate {
+                story_id: self.story_id,
+            })),
             "d" => Ok(Some(Action::DeleteStory {
                 epic_id: self.epic_id,
                 story_id: self.story_id,
             })),
+            "l" => Ok(Some(Action::LogWork {
+                story_id: self.story_id,
+            })),
+            "a" => Ok(Some(Action::AddStoryLink {
+                story_id: self.story_id,
+            })),
+            "r" => Ok(Some(Action::RemoveStoryLink {
+                story_id: self.story_id,
+            })),
+            "s" | "x" => {
+                // only the key for what the timer can do next is shown and accepted
+                let running = self
+                    .db
+                    .read_db()?
+                    .get_timer(self.story_id, &current_user())
+                    .is_some();
+                Ok(match (input, running) {
+                    ("s", false) => Some(Action::StartTimer {
+                        story_id: self.story_id,
+                    }),
+                    ("x", true) => Some(Action::StopTimer {
+                        story_id: self.story_id,
+                    }),
+                    _ => None,
+                })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Board {
+    // None shows the stories of every epic on one board
+    pub epic_id: Option<u32>,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Board {
+    // min width of each status column
+    const MIN_COLUMN_WIDTH: usize = 8;
+
+    // returns (story id, epic id) pairs for every story on the board, sorted by story id
+    fn get_story_ids(&self, db_state: &DBState) -> Result<Vec<(u32, u32)>> {
+        let epics = match self.epic_id {
+            Some(epic_id) => {
+                let epic = db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic!"))?;
+                vec![(epic_id, epic)]
+            }
+            None => db_state
+                .epics
+                .iter()
+                .map(|(id, epic)| (*id, epic))
+                .collect(),
+        };
+
+        Ok(epics
+            .into_iter()
+            .flat_map(|(epic_id, epic)| epic.stories.iter().map(move |id| (*id, epic_id)))
+            .filter(|(story_id, _)| db_state.stories.contains_key(story_id))
+            .sorted()
+            .collect())
+    }
+}
+
+impl Page for Board {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let story_ids = self.get_story_ids(&db_state)?;
+
+        let table = Table::new(
+            Status::ALL
+                .iter()
+                .map(|status| {
+                    ColumnSpec::new(
+                        get_theme().paint_status(status),
+                        Self::MIN_COLUMN_WIDTH,
+                        usize::MAX,
+                        1,
+                    )
+                })
+                .collect(),
+        );
+
+        match self.epic_id {
+            Some(epic_id) => println!(
+                "{}",
+                table.get_title_string(&format!("BOARD: EPIC {}", epic_id))
+            ),
+            None => println!("{}", table.get_title_string("BOARD")),
+        }
+        println!("{}", table.get_header_string());
+
+        let columns: Vec<Vec<String>> = Status::ALL
+            .iter()
+            .map(|status| {
+                story_ids
+                    .iter()
+                    .map(|(story_id, _)| (story_id, &db_state.stories[story_id]))
+                    .filter(|(_, story)| &story.status == status)
+                    .map(|(story_id, story)| format!("#{} {}", story_id, story.name))
+                    .collect()
+            })
+            .collect();
+
+        let row_count = columns.iter().map(|cards| cards.len()).max().unwrap_or(0);
+        for row in 0..row_count {
+            let cards: Vec<&str> = columns
+                .iter()
+                .map(|cards| cards.get(row).map(String::as_str).unwrap_or(""))
+                .collect();
+            println!("{}", table.get_row_string(&cards));
+        }
+
+        println!("\n\n");
+        println!(
+            "{}",
+            get_command_bar_string(
+                "[p] previous | [m :id: :status:] move story | [:id:] navigate to story"
+            )
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        if input == "p" {
+            return Ok(Some(Action::NavigateToPreviousPage));
+        }
+
+        let story_ids = self.get_story_ids(&self.db.read_db()?)?;
+        let find_story = |id: &str| {
+            id.parse::<u32>()
+                .ok()
+                .and_then(|id| story_ids.iter().find(|(story_id, _)| *story_id == id))
+        };
+
+        // m <id> <status>, where the status may contain a space e.g. "m 9 in progress"
+        if let Some(("m", args)) = input.split_once(' ') {
+            if let Some((id, status)) = args.split_once(' ') {
+                if let (Some((story_id, _)), Ok(status)) = (find_story(id), status.parse()) {
+                    return Ok(Some(Action::MoveStory {
+                        story_id: *story_id,
+                        status,
+                    }));
+                }
+            }
+            return Ok(None);
+        }
+
+        Ok(
+            find_story(input).map(|(story_id, epic_id)| Action::NavigateToStoryDetail {
+                epic_id: *epic_id,
+                story_id: *story_id,
+            }),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// An epic or story on the Overdue page
+struct OverdueItem<'a> {
+    kind: &'static str,
+    id: u32,
+    // the epic itself for epics
+    epic_id: u32,
+    item: &'a dyn ListItem,
+    days_late: i64,
+}
+
+// Every epic and story that is past its due date and not done, across all epics
+pub struct Overdue {
+    pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl Overdue {
+    // lines printed around the table: 2 header lines, 3 footer lines and the input line
+    const RESERVED_LINES: usize = 6;
+
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            pager: Pager::default(),
+        }
+    }
+
+    // the latest first, then by id
+    fn get_items(db_state: &DBState, today: NaiveDate) -> Vec<OverdueItem<'_>> {
+        let mut items = Vec::new();
+        for (epic_id, epic) in &db_state.epics {
+            if epic.is_overdue(today) {
+                items.push(OverdueItem {
+                    kind: "epic",
+                    id: *epic_id,
+                    epic_id: *epic_id,
+                    item: epic,
+                    days_late: epic
+                        .due_date
+                        .map_or(0, |due_date| get_days_late(due_date, today)),
+                });
+            }
+            for story_id in &epic.stories {
+                let Some(story) = db_state.stories.get(story_id) else {
+                    continue;
+                };
+                if story.is_overdue(today) {
+                    items.push(OverdueItem {
+                        kind: "story",
+                        id: *story_id,
+                        epic_id: *epic_id,
+                        item: story,
+                        days_late: story
+                            .due_date
+                            .map_or(0, |due_date| get_days_late(due_date, today)),
+                    });
+                }
+            }
+        }
+        items.sort_by_key(|item| (-item.days_late, item.id));
+        items
+    }
+}
+
+impl Page for Overdue {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let today = today();
+        let items = Self::get_items(&db_state, today);
+
+        let table = Table::new(vec![
+            ColumnSpec::new("type", 5, 5, 0),
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new("status", 11, 17, 1),
+            ColumnSpec::new("due", 11, 11, 0),
+            ColumnSpec::new("days late", 9, 9, 0),
+        ]);
+        println!("{}", table.get_title_string("OVERDUE"));
+        println!("{}", table.get_header_string());
+
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        for (row, item) in self.pager.get_page(&items, page_size).iter().enumerate() {
+            let row_string = table.get_row_string(&[
+                item.kind,
+                &item.id.to_string(),
+                item.item.name(),
+                &get_theme().paint_status(item.item.status()),
+                &get_theme().paint_due_date(item.item, today),
+                &item.days_late.to_string(),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
+        }
+
+        println!("\n{}", self.pager.get_footer(items.len(), page_size));
+        println!(
+            "{}",
+            get_command_bar_string(
+                "[p] previous | [>] next page | [<] previous page | [:id:] navigate to epic or story"
+            )
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+                let db_state = self.db.read_db()?;
+                let items = Self::get_items(&db_state, today());
+                Ok(items
+                    .iter()
+                    .find(|item| item.id == id)
+                    .map(|item| match item.kind {
+                        "epic" => Action::NavigateToEpicDetail { epic_id: item.id },
+                        _ => Action::NavigateToStoryDetail {
+                            epic_id: item.epic_id,
+                            story_id: item.id,
+                        },
+                    }))
+            }
+        }
+    }
+
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let items = Self::get_items(&db_state, today());
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| items.get(index))
+            .map(|item| item.id.to_string()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Every sprint, the latest first
+pub struct Sprints {
+    pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl Sprints {
+    // lines printed around the table: 2 header lines, 3 footer lines and the input line
+    const RESERVED_LINES: usize = 6;
+
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            pager: Pager::default(),
+        }
+    }
+}
+
+impl Page for Sprints {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let sprints = db_state.get_sorted_sprints();
+
+        let table = Table::new(vec![
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new("start", 10, 10, 0),
+            ColumnSpec::new("end", 10, 10, 0),
+            ColumnSpec::new("stories", 7, 7, 0),
+            ColumnSpec::new("state", 6, 6, 0),
+        ]);
+        println!("{}", table.get_title_string("SPRINTS"));
+        println!("{}", table.get_header_string());
+
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        for (row, (id, sprint)) in self.pager.get_page(&sprints, page_size).iter().enumerate() {
+            let row_string = table.get_row_string(&[
+                &id.to_string(),
+                &sprint.name,
+                &sprint.start_date.to_string(),
+                &sprint.end_date.to_string(),
+                &sprint.stories.len().to_string(),
+                get_sprint_state(sprint),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
+        }
+
+        println!("\n{}", self.pager.get_footer(sprints.len(), page_size));
+        println!(
+            "{}",
+            get_command_bar_string(
+                "[p] previous | [c] create sprint | [>] next page | [<] previous page | [:id:] navigate to sprint"
+            )
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "c" => Ok(Some(Action::CreateSprint)),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
+            input => {
+                if let Ok(valid_int) = input.parse::<u32>() {
+                    let sprints = self.db.read_db()?.sprints;
+                    if sprints.contains_key(&valid_int) {
+                        return Ok(Some(Action::NavigateToSprintDetail {
+                            sprint_id: valid_int,
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let sprints = db_state.get_sorted_sprints();
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| sprints.get(index))
+            .map(|(id, _)| id.to_string()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn get_sprint_state(sprint: &Sprint) -> &'static str {
+    if sprint.closed {
+        "closed"
+    } else {
+        "open"
+    }
+}
+
+// The stories planned in a sprint, from any epic
+pub struct SprintDetail {
+    pub sprint_id: u32,
+    pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl SprintDetail {
+    // lines printed around the stories table: 5 lines of sprint details, 4 lines of status
+    // breakdown and story headers, 6 footer lines and the input line
+    const RESERVED_LINES: usize = 16;
+
+    pub fn new(sprint_id: u32, db: Rc<JiraDatabase>) -> Self {
+        Self {
+            sprint_id,
+            db,
+            pager: Pager::default(),
+        }
+    }
+
+    // (story id, epic id, story) for each story in the order they were added to the sprint
+    fn get_stories<'a>(db_state: &'a DBState, sprint: &Sprint) -> Vec<(u32, u32, &'a Story)> {
+        sprint
+            .stories
+            .iter()
+            .filter_map(|id| {
+                let story = db_state.stories.get(id)?;
+                Some((*id, db_state.get_epic_id(*id)?, story))
+            })
+            .collect()
+    }
+
+    // how many of the sprint's stories are in each status, in workflow order
+    fn get_status_breakdown(stories: &[(u32, u32, &Story)]) -> Vec<(Status, usize)> {
+        Status::ALL
+            .iter()
+            .map(|status| {
+                let count = stories
+                    .iter()
+                    .filter(|(_, _, story)| &story.status == status)
+                    .count();
+                (status.clone(), count)
+            })
+            .collect()
+    }
+}
+
+impl Page for SprintDetail {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let sprint = db_state
+            .sprints
+            .get(&self.sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint!"))?;
+
+        let table = Table::new(vec![
+            ColumnSpec::new("id", 4, 8, 1),
+            ColumnSpec::new("name", 10, 40, 2),
+            ColumnSpec::new("goal", 10, usize::MAX, 4),
+            ColumnSpec::new("start", 10, 10, 0),
+            ColumnSpec::new("end", 10, 10, 0),
+            ColumnSpec::new("state", 6, 6, 0),
+        ]);
+        println!("{}", table.get_title_string("SPRINT"));
+        println!("{}", table.get_header_string());
+        println!(
+            "{}",
+            table.get_row_string(&[
+                &self.sprint_id.to_string(),
+                &sprint.name,
+                &sprint.goal,
+                &sprint.start_date.to_string(),
+                &sprint.end_date.to_string(),
+                get_sprint_state(sprint),
+            ])
+        );
+
+        let stories = Self::get_stories(&db_state, sprint);
+        let breakdown = Self::get_status_breakdown(&stories)
+            .iter()
+            .map(|(status, count)| format!("{} {}", get_theme().paint_status(status), count))
+            .join(" | ");
+        println!("\n{}\n", breakdown);
+
+        let table = Table::new(vec![
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("epic", 4, 12, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new("status", 11, 17, 1),
+            ColumnSpec::new("due", 11, 11, 0),
+        ]);
+        println!("{}", table.get_title_string("STORIES"));
+        println!("{}", table.get_header_string());
+
+        let page_size = get_page_size(Self::RESERVED_LINES);
+        let today = today();
+        for (row, (id, epic_id, story)) in
+            self.pager.get_page(&stories, page_size).iter().enumerate()
+        {
+            let row_string = table.get_row_string(&[
+                &id.to_string(),
+                &epic_id.to_string(),
+                &story.name,
+                &get_theme().paint_status(&story.status),
+                &get_theme().paint_due_date(*story, today),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
+        }
+
+        println!("\n{}", self.pager.get_footer(stories.len(), page_size));
+        println!("\n\n");
+        if sprint.closed {
+            println!(
+                "{}",
+                get_command_bar_string(
+                    "[p] previous | [>] next page | [<] previous page | [:id:] navigate to story"
+                )
+            );
+        } else {
+            println!("{}", get_command_bar_string("[p] previous | [a] add story | [r] remove story | [x] close sprint | [g] burndown | [>] next page | [<] previous page | [:id:] navigate to story"));
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        let db_state = self.db.read_db()?;
+        let sprint = db_state
+            .sprints
+            .get(&self.sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint!"))?;
+        let sprint_id = self.sprint_id;
+
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "a" if !sprint.closed => Ok(Some(Action::AddStoryToSprint { sprint_id })),
+            "r" if !sprint.closed => Ok(Some(Action::RemoveStoryFromSprint { sprint_id })),
+            "x" if !sprint.closed => Ok(Some(Action::CloseSprint { sprint_id })),
+            "g" => Ok(Some(Action::NavigateToChart {
+                scope: ChartScope::Sprint(sprint_id),
+            })),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+                Ok(Self::get_stories(&db_state, sprint)
+                    .iter()
+                    .find(|(story_id, _, _)| *story_id == id)
+                    .map(|(story_id, epic_id, _)| Action::NavigateToStoryDetail {
+                        epic_id: *epic_id,
+                        story_id: *story_id,
+                    }))
+            }
+        }
+    }
+
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let db_state = self.db.read_db()?;
+        let sprint = db_state
+            .sprints
+            .get(&self.sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint!"))?;
+        let stories = Self::get_stories(&db_state, sprint);
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| stories.get(index))
+            .map(|(id, _, _)| id.to_string()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Burndown or burnup chart of an epic, a sprint or every story, see charts
+pub struct Chart {
+    pub scope: ChartScope,
+    pub db: Rc<JiraDatabase>,
+    kind: Cell<ChartKind>,
+}
+
+impl Chart {
+    // lines printed around the bars: the title, 3 lines of axis and legend, 4 footer lines and
+    // the input line
+    const RESERVED_LINES: usize = 9;
+    // taller charts are no easier to read
+    const MAX_HEIGHT: usize = 20;
+
+    pub fn new(scope: ChartScope, db: Rc<JiraDatabase>) -> Self {
+        Self {
+            scope,
+            db,
+            kind: Cell::new(ChartKind::Burndown),
+        }
+    }
+}
+
+impl Page for Chart {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read_db()?;
+        let today = today();
+        let (start, end) = get_default_range(&db_state, self.scope, today)?;
+        let points = get_series(&db_state, self.scope, start, end, today)?;
+
+        let kind = self.kind.get();
+        let title = match kind {
+            ChartKind::Burndown => "BURNDOWN",
+            ChartKind::Burnup => "BURNUP",
+        };
+        let title = match self.scope {
+            ChartScope::All => title.to_owned(),
+            ChartScope::Epic(epic_id) => format!("{}: EPIC {}", title, epic_id),
+            ChartScope::Sprint(sprint_id) => format!("{}: SPRINT {}", title, sprint_id),
+        };
+        let width = get_terminal_width();
+        let table = Table::with_width(vec![ColumnSpec::new("", 1, usize::MAX, 1)], width);
+        println!("{}", table.get_title_string(&title));
+
+        let height = get_page_size(Self::RESERVED_LINES).min(Self::MAX_HEIGHT);
+        for line in draw_chart(&points, kind, width, height, false) {
+            println!("{}", line);
+        }
+
+        println!("\n\n");
+        println!(
+            "{}",
+            get_command_bar_string("[p] previous | [m] switch burndown/burnup")
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "m" => {
+                self.kind.set(self.kind.get().toggled());
+                Ok(None)
+            }
             _ => Ok(None),
         }
     }
@@ -198,47 +1338,253 @@ impl Page for StoryDetail {
     }
 }
 
+// Counts per status, progress of each epic and how fast stories get done, see stats
+pub struct StatsDashboard {
+    pub db: Rc<JiraDatabase>,
+    pager: Pager,
+}
+
+impl StatsDashboard {
+    // lines printed around the epics table without the rows of the largest open epics: 6 lines of
+    // status counts, 7 lines of age, throughput and headers, 3 for the largest open epics, 2 for
+    // the epics headers, 3 footer lines and the input line
+    const RESERVED_LINES: usize = 22;
+
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            pager: Pager::default(),
+        }
+    }
+
+    fn get_epics_table(title: &str) -> Table {
+        Table::new(vec![
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new(title, 11, 17, 1),
+            ColumnSpec::new("stories", 7, 7, 0),
+            ColumnSpec::new("complete", 8, 8, 0),
+        ])
+    }
+}
+
+impl Page for StatsDashboard {
+    fn draw_page(&self) -> Result<()> {
+        let stats = get_stats(&self.db.read_db()?, today());
+
+        let table = Table::new(vec![
+            ColumnSpec::new("status", 11, 17, 1),
+            ColumnSpec::new("epics", 5, 10, 1),
+            ColumnSpec::new("stories", 7, 10, 1),
+        ]);
+        println!("{}", table.get_title_string("STATS"));
+        println!("{}", table.get_header_string());
+        for (status, epics) in &stats.epics_by_status {
+            println!(
+                "{}",
+                table.get_row_string(&[
+                    &get_theme().paint_status(status),
+                    &epics.to_string(),
+                    &stats.stories_by_status[status].to_string(),
+                ])
+            );
+        }
+
+        let age = stats
+            .average_open_story_age_days
+            .map_or("unknown".to_owned(), |age| format!("{:.1} days", age));
+        println!(
+            "\naverage age of open stories: {} | throughput: {:.1} stories/week\n",
+            age, stats.average_weekly_throughput
+        );
+        let table = Table::new(
+            stats
+                .weekly_throughput
+                .iter()
+                .map(|week| ColumnSpec::new(week.week_start.format("%m-%d").to_string(), 5, 10, 1))
+                .collect(),
+        );
+        println!("{}", table.get_header_string());
+        let counts: Vec<String> = stats
+            .weekly_throughput
+            .iter()
+            .map(|week| week.completed.to_string())
+            .collect();
+        println!(
+            "{}",
+            table.get_row_string(&counts.iter().map(String::as_str).collect::<Vec<_>>())
+        );
+
+        let table = Self::get_epics_table("open");
+        println!("\n{}", table.get_title_string("LARGEST OPEN EPICS"));
+        println!("{}", table.get_header_string());
+        for epic in &stats.largest_open_epics {
+            println!(
+                "{}",
+                table.get_row_string(&[
+                    &epic.id.to_string(),
+                    &epic.name,
+                    &epic.open_stories.to_string(),
+                    &epic.stories.to_string(),
+                    &format!("{:.0}%", epic.percent_complete),
+                ])
+            );
+        }
+
+        let table = Self::get_epics_table("status");
+        println!("\n{}", table.get_title_string("EPICS"));
+        println!("{}", table.get_header_string());
+        let page_size = get_page_size(Self::RESERVED_LINES + stats.largest_open_epics.len());
+        for (row, epic) in self
+            .pager
+            .get_page(&stats.epics, page_size)
+            .iter()
+            .enumerate()
+        {
+            let row_string = table.get_row_string(&[
+                &epic.id.to_string(),
+                &epic.name,
+                &get_theme().paint_status(&epic.status),
+                &epic.stories.to_string(),
+                &format!("{:.0}%", epic.percent_complete),
+            ]);
+            println!(
+                "{}",
+                get_row_string(row_string, self.pager.is_selected(row))
+            );
+        }
+
+        println!("\n{}", self.pager.get_footer(stats.epics.len(), page_size));
+        println!(
+            "{}",
+            get_command_bar_string(
+                "[p] previous | [>] next page | [<] previous page | [:id:] navigate to epic"
+            )
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        // match against the user input and return the corresponding action. If the user input was invalid return None.
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            ">" => {
+                self.pager.next_page();
+                Ok(None)
+            }
+            "<" => {
+                self.pager.previous_page();
+                Ok(None)
+            }
+            input => {
+                if let Ok(valid_int) = input.parse::<u32>() {
+                    let epics = self.db.read_db()?.epics;
+                    if epics.contains_key(&valid_int) {
+                        return Ok(Some(Action::NavigateToEpicDetail { epic_id: valid_int }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn move_selection(&self, offset: isize) {
+        self.pager.move_selection(offset);
+    }
+
+    fn get_selected_input(&self) -> Result<Option<String>> {
+        let stats = get_stats(&self.db.read_db()?, today());
+        Ok(self
+            .pager
+            .get_selected()
+            .and_then(|index| stats.epics.get(index))
+            .map(|epic| epic.id.to_string()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::test_utils::MockDB;
-    use crate::models::{Epic, Story};
+    use crate::models::{Epic, LinkKind};
+
+    mod projects {
+        use super::*;
+        use crate::models::Project;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            db.create_project(Project::new("".to_owned(), "WEB".to_owned()))
+                .unwrap();
+
+            let page = Projects::new(db);
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let project_id = db
+                .create_project(Project::new("".to_owned(), "WEB".to_owned()))
+                .unwrap();
+            let page = Projects::new(db);
+
+            assert_eq!(page.handle_input("q").unwrap(), Some(Action::Exit));
+            assert_eq!(page.handle_input("c").unwrap(), Some(Action::CreateProject));
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::NavigateToProject { project_id: None })
+            );
+            assert_eq!(
+                page.handle_input(&project_id.to_string()).unwrap(),
+                Some(Action::NavigateToProject {
+                    project_id: Some(project_id)
+                })
+            );
+            assert_eq!(page.handle_input("999").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+            assert_eq!(page.handle_input("q\n").unwrap(), None);
+        }
+    }
 
     mod home_page {
         use super::*;
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
-            let page = HomePage { db };
-            assert_eq!(page.draw_page().is_ok(), true);
+            let page = HomePage::new(db);
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
-            let page = HomePage { db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            let page = HomePage::new(db);
+            assert!(page.handle_input("").is_ok());
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic = Epic::new("".to_owned(), "".to_owned());
             let epic_id = db.create_epic(epic).unwrap();
-            let page = HomePage { db };
+            let page = HomePage::new(db);
 
             let q = "q";
             let c = "c";
+            let b = "b";
+            let s = "s";
+            let r = "r";
             let valid_epic_id = epic_id.to_string();
             let invalid_epic_id = "999";
             let junk_input = "j983f2j";
@@ -246,7 +1592,49 @@ mod tests {
             let input_with_trailing_white_spaces = "q\n";
 
             assert_eq!(page.handle_input(q).unwrap(), Some(Action::Exit));
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
             assert_eq!(page.handle_input(c).unwrap(), Some(Action::CreateEpic));
+            assert_eq!(
+                page.handle_input("C").unwrap(),
+                Some(Action::CreateEpicInEditor)
+            );
+            assert_eq!(
+                page.handle_input(b).unwrap(),
+                Some(Action::NavigateToBoard { epic_id: None })
+            );
+            assert_eq!(
+                page.handle_input("o").unwrap(),
+                Some(Action::NavigateToOverdue)
+            );
+            assert_eq!(
+                page.handle_input("S").unwrap(),
+                Some(Action::NavigateToSprints)
+            );
+            assert_eq!(
+                page.handle_input("g").unwrap(),
+                Some(Action::NavigateToChart {
+                    scope: ChartScope::All
+                })
+            );
+            assert_eq!(
+                page.handle_input("D").unwrap(),
+                Some(Action::NavigateToStats)
+            );
+            assert_eq!(
+                page.handle_input(s).unwrap(),
+                Some(Action::CycleSortKey {
+                    page: ListPage::Home
+                })
+            );
+            assert_eq!(
+                page.handle_input(r).unwrap(),
+                Some(Action::ToggleSortDirection {
+                    page: ListPage::Home
+                })
+            );
             assert_eq!(
                 page.handle_input(&valid_epic_id).unwrap(),
                 Some(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -262,6 +1650,21 @@ mod tests {
                 None
             );
         }
+        #[test]
+        fn handle_input_should_change_page() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+            let page = HomePage::new(db);
+            assert_eq!(page.handle_input(">").unwrap(), None);
+            assert_eq!(page.pager.get_current_page(), 1);
+            assert_eq!(page.handle_input("<").unwrap(), None);
+            assert_eq!(page.pager.get_current_page(), 0);
+
+            // there is only one page of epics so drawing moves back onto it
+            page.handle_input(">").unwrap();
+            page.draw_page().unwrap();
+            assert_eq!(page.pager.get_current_page(), 0);
+        }
     }
 
     mod epic_detail_page {
@@ -269,44 +1672,51 @@ mod tests {
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.draw_page().is_ok(), true);
+            let page = EpicDetail::new(epic_id, db);
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.handle_input("").is_ok(), true);
-            #[test]
-            fn draw_page_should_throw_error_for_invalid_epic_id() {
-                let db = Rc::new(JiraDatabase {
-                    database: Box::new(MockDB::new()),
-                });
+            let page = EpicDetail::new(epic_id, db);
+            assert!(page.handle_input("").is_ok());
+        }
 
-                let page = EpicDetail { epic_id: 999, db };
-                assert_eq!(page.draw_page().is_err(), true);
-            }
+        #[test]
+        fn handle_input_should_change_page() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+            assert_eq!(page.handle_input(">").unwrap(), None);
+            assert_eq!(page.pager.get_current_page(), 1);
+            assert_eq!(page.handle_input("<").unwrap(), None);
+            assert_eq!(page.pager.get_current_page(), 0);
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_epic_id() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+            let page = EpicDetail::new(999, db);
+            assert!(page.draw_page().is_err());
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -315,12 +1725,15 @@ mod tests {
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
 
-            let page = EpicDetail { epic_id, db };
+            let page = EpicDetail::new(epic_id, db);
 
             let p = "p";
             let u = "u";
             let d = "d";
             let c = "c";
+            let b = "b";
+            let s = "s";
+            let r = "r";
             let invalid_story_id = "999";
             let junk_input = "j983f2j";
             let junk_input_with_valid_prefix = "p983f2j";
@@ -330,10 +1743,28 @@ mod tests {
                 page.handle_input(p).unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
+            assert_eq!(
+                page.handle_input("t").unwrap(),
+                Some(Action::UpdateEpicDueDate { epic_id })
+            );
+            assert_eq!(
+                page.handle_input("m").unwrap(),
+                Some(Action::MoveEpic { epic_id })
+            );
+            assert_eq!(
+                page.handle_input("g").unwrap(),
+                Some(Action::NavigateToChart {
+                    scope: ChartScope::Epic(epic_id)
+                })
+            );
             assert_eq!(
                 page.handle_input(u).unwrap(),
                 Some(Action::UpdateEpicStatus { epic_id: 1 })
             );
+            assert_eq!(
+                page.handle_input("e").unwrap(),
+                Some(Action::EditEpic { epic_id: 1 })
+            );
             assert_eq!(
                 page.handle_input(d).unwrap(),
                 Some(Action::DeleteEpic { epic_id: 1 })
@@ -342,6 +1773,26 @@ mod tests {
                 page.handle_input(c).unwrap(),
                 Some(Action::CreateStory { epic_id: 1 })
             );
+            assert_eq!(
+                page.handle_input("C").unwrap(),
+                Some(Action::CreateStoryInEditor { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input(b).unwrap(),
+                Some(Action::NavigateToBoard { epic_id: Some(1) })
+            );
+            assert_eq!(
+                page.handle_input(s).unwrap(),
+                Some(Action::CycleSortKey {
+                    page: ListPage::EpicDetail
+                })
+            );
+            assert_eq!(
+                page.handle_input(r).unwrap(),
+                Some(Action::ToggleSortDirection {
+                    page: ListPage::EpicDetail
+                })
+            );
             assert_eq!(
                 page.handle_input(&story_id.to_string()).unwrap(),
                 Some(Action::NavigateToStoryDetail {
@@ -367,9 +1818,7 @@ mod tests {
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -381,16 +1830,31 @@ mod tests {
             let page = StoryDetail {
                 epic_id,
                 story_id,
-                db,
+                db: Rc::clone(&db),
             };
-            assert_eq!(page.draw_page().is_ok(), true);
+            assert!(page.draw_page().is_ok());
+
+            db.add_worklog(Worklog {
+                story_id,
+                user: "ann".to_owned(),
+                date: today(),
+                minutes: 90,
+                note: "review".to_owned(),
+                story_name: "".to_owned(),
+            })
+            .unwrap();
+            db.start_timer(story_id, &current_user()).unwrap();
+            let blocker_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.add_story_link(story_id, LinkKind::BlockedBy, blocker_id)
+                .unwrap();
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -404,14 +1868,12 @@ mod tests {
                 story_id,
                 db,
             };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            assert!(page.handle_input("").is_ok());
         }
 
         #[test]
         fn draw_page_should_throw_error_for_invalid_story_id() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -425,14 +1887,12 @@ mod tests {
                 story_id: 999,
                 db,
             };
-            assert_eq!(page.draw_page().is_err(), true);
+            assert!(page.draw_page().is_err());
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -463,10 +1923,41 @@ mod tests {
                 page.handle_input(u).unwrap(),
                 Some(Action::UpdateStoryStatus { story_id })
             );
+            assert_eq!(
+                page.handle_input("t").unwrap(),
+                Some(Action::UpdateStoryDueDate { story_id })
+            );
+            assert_eq!(
+                page.handle_input("e").unwrap(),
+                Some(Action::EditStory { story_id })
+            );
             assert_eq!(
                 page.handle_input(d).unwrap(),
                 Some(Action::DeleteStory { epic_id, story_id })
             );
+            assert_eq!(
+                page.handle_input("l").unwrap(),
+                Some(Action::LogWork { story_id })
+            );
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AddStoryLink { story_id })
+            );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::RemoveStoryLink { story_id })
+            );
+            assert_eq!(
+                page.handle_input("s").unwrap(),
+                Some(Action::StartTimer { story_id })
+            );
+            assert_eq!(page.handle_input("x").unwrap(), None);
+            page.db.start_timer(story_id, &current_user()).unwrap();
+            assert_eq!(page.handle_input("s").unwrap(), None);
+            assert_eq!(
+                page.handle_input("x").unwrap(),
+                Some(Action::StopTimer { story_id })
+            );
             assert_eq!(page.handle_input(some_number).unwrap(), None);
             assert_eq!(page.handle_input(junk_input).unwrap(), None);
             assert_eq!(
@@ -479,4 +1970,391 @@ mod tests {
             );
         }
     }
+
+    mod board_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = Board {
+                epic_id: Some(epic_id),
+                db: Rc::clone(&db),
+            };
+            assert!(page.draw_page().is_ok());
+
+            let page = Board { epic_id: None, db };
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_epic_id() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+            let page = Board {
+                epic_id: Some(999),
+                db,
+            };
+            assert!(page.draw_page().is_err());
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let other_epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+                .unwrap();
+
+            let page = Board {
+                epic_id: Some(epic_id),
+                db: Rc::clone(&db),
+            };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&format!("m {} resolved", story_id))
+                    .unwrap(),
+                Some(Action::MoveStory {
+                    story_id,
+                    status: Status::Resolved
+                })
+            );
+            assert_eq!(
+                page.handle_input(&format!("m {} in progress", story_id))
+                    .unwrap(),
+                Some(Action::MoveStory {
+                    story_id,
+                    status: Status::InProgress
+                })
+            );
+            assert_eq!(
+                page.handle_input(&format!("m {} 4", story_id)).unwrap(),
+                Some(Action::MoveStory {
+                    story_id,
+                    status: Status::Closed
+                })
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            // stories of other epics are not on this board
+            assert_eq!(
+                page.handle_input(&format!("m {} closed", other_story_id))
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                page.handle_input(&other_story_id.to_string()).unwrap(),
+                None
+            );
+            assert_eq!(
+                page.handle_input(&format!("m {} done", story_id)).unwrap(),
+                None
+            );
+            assert_eq!(page.handle_input("m 999 closed").unwrap(), None);
+            assert_eq!(page.handle_input("m").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+            assert_eq!(page.handle_input("p\n").unwrap(), None);
+
+            let page = Board { epic_id: None, db };
+
+            assert_eq!(
+                page.handle_input(&format!("m {} closed", other_story_id))
+                    .unwrap(),
+                Some(Action::MoveStory {
+                    story_id: other_story_id,
+                    status: Status::Closed
+                })
+            );
+            assert_eq!(
+                page.handle_input(&other_story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: other_epic_id,
+                    story_id: other_story_id
+                })
+            );
+        }
+    }
+
+    mod overdue_page {
+        use super::*;
+
+        // an epic due yesterday with a story due a week ago, a resolved story due a week ago and
+        // a story due tomorrow
+        fn get_db() -> Rc<JiraDatabase> {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let today = today();
+            let mut epic = Epic::new("".to_owned(), "".to_owned());
+            epic.due_date = today.pred_opt();
+            let epic_id = db.create_epic(epic).unwrap();
+            for (days, status) in [
+                (-7, Status::InProgress),
+                (-7, Status::Resolved),
+                (1, Status::Open),
+            ] {
+                let mut story = Story::new("".to_owned(), "".to_owned());
+                story.due_date = Some(today + chrono::Duration::days(days));
+                story.status = status;
+                db.create_story(story, epic_id).unwrap();
+            }
+            db
+        }
+
+        #[test]
+        fn get_items_should_list_overdue_items_latest_first() {
+            let db_state = get_db().read_db().unwrap();
+
+            let items: Vec<_> = Overdue::get_items(&db_state, today())
+                .iter()
+                .map(|item| (item.kind, item.id, item.days_late))
+                .collect();
+
+            assert_eq!(items, vec![("story", 2, 7), ("epic", 1, 1)]);
+        }
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let page = Overdue::new(get_db());
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let page = Overdue::new(get_db());
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input("1").unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("2").unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
+            );
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod sprint_pages {
+        use super::*;
+        use crate::models::Sprint;
+
+        // a sprint with an open and a resolved story from different epics
+        fn get_db() -> (Rc<JiraDatabase>, u32) {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let sprint_id = db
+                .create_sprint(Sprint::new("".to_owned(), "".to_owned(), today(), today()))
+                .unwrap();
+            for status in [Status::Open, Status::Resolved] {
+                let epic_id = db
+                    .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                    .unwrap();
+                let mut story = Story::new("".to_owned(), "".to_owned());
+                story.status = status;
+                let story_id = db.create_story(story, epic_id).unwrap();
+                db.add_story_to_sprint(sprint_id, story_id).unwrap();
+            }
+            (db, sprint_id)
+        }
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let (db, sprint_id) = get_db();
+            assert!(Sprints::new(Rc::clone(&db)).draw_page().is_ok());
+            assert!(SprintDetail::new(sprint_id, Rc::clone(&db))
+                .draw_page()
+                .is_ok());
+            assert!(SprintDetail::new(999, db).draw_page().is_err());
+        }
+
+        #[test]
+        fn sprints_handle_input_should_return_the_correct_actions() {
+            let (db, sprint_id) = get_db();
+            let page = Sprints::new(db);
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(page.handle_input("c").unwrap(), Some(Action::CreateSprint));
+            assert_eq!(
+                page.handle_input(&sprint_id.to_string()).unwrap(),
+                Some(Action::NavigateToSprintDetail { sprint_id })
+            );
+            assert_eq!(page.handle_input("999").unwrap(), None);
+        }
+
+        #[test]
+        fn sprint_detail_should_list_stories_from_every_epic() {
+            let (db, sprint_id) = get_db();
+            let db_state = db.read_db().unwrap();
+
+            let stories = SprintDetail::get_stories(&db_state, &db_state.sprints[&sprint_id]);
+
+            let ids: Vec<_> = stories
+                .iter()
+                .map(|(id, epic_id, _)| (*id, *epic_id))
+                .collect();
+            assert_eq!(ids, vec![(3, 2), (5, 4)]);
+            assert_eq!(
+                SprintDetail::get_status_breakdown(&stories),
+                vec![
+                    (Status::Open, 1),
+                    (Status::InProgress, 0),
+                    (Status::Resolved, 1),
+                    (Status::Closed, 0),
+                ]
+            );
+        }
+
+        #[test]
+        fn sprint_detail_handle_input_should_return_the_correct_actions() {
+            let (db, sprint_id) = get_db();
+            let page = SprintDetail::new(sprint_id, Rc::clone(&db));
+
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AddStoryToSprint { sprint_id })
+            );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::RemoveStoryFromSprint { sprint_id })
+            );
+            assert_eq!(
+                page.handle_input("x").unwrap(),
+                Some(Action::CloseSprint { sprint_id })
+            );
+            assert_eq!(
+                page.handle_input("g").unwrap(),
+                Some(Action::NavigateToChart {
+                    scope: ChartScope::Sprint(sprint_id)
+                })
+            );
+            assert_eq!(
+                page.handle_input("5").unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 4,
+                    story_id: 5
+                })
+            );
+            assert_eq!(page.handle_input("2").unwrap(), None);
+
+            // a closed sprint can't be changed
+            db.close_sprint(sprint_id, None).unwrap();
+            assert_eq!(page.handle_input("a").unwrap(), None);
+            assert_eq!(page.handle_input("x").unwrap(), None);
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+        }
+    }
+
+    mod chart_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            assert!(Chart::new(ChartScope::All, Rc::clone(&db))
+                .draw_page()
+                .is_ok());
+            assert!(Chart::new(ChartScope::Epic(epic_id), Rc::clone(&db))
+                .draw_page()
+                .is_ok());
+            assert!(Chart::new(ChartScope::Sprint(999), db).draw_page().is_err());
+        }
+
+        #[test]
+        fn handle_input_should_switch_the_chart_kind() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let page = Chart::new(ChartScope::All, db);
+
+            assert_eq!(page.handle_input("m").unwrap(), None);
+            assert_eq!(page.kind.get(), ChartKind::Burnup);
+            assert_eq!(page.handle_input("m").unwrap(), None);
+            assert_eq!(page.kind.get(), ChartKind::Burndown);
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(page.handle_input("x").unwrap(), None);
+        }
+    }
+
+    mod stats_dashboard {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let page = StatsDashboard::new(Rc::clone(&db));
+            assert!(page.draw_page().is_ok());
+
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let page = StatsDashboard::new(db);
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&epic_id.to_string()).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id })
+            );
+            assert_eq!(page.handle_input("999").unwrap(), None);
+
+            page.move_selection(0);
+            assert_eq!(
+                page.get_selected_input().unwrap(),
+                Some(epic_id.to_string())
+            );
+        }
+    }
 }
@@ -1,27 +1,247 @@
-// this is a trait implemented on types e.g.String so we need to bring entirety into scope
-// rather than importing e.g. just a function
-use ellipse::Ellipse;
+use std::cell::Cell;
+
+use crossterm::{style::Stylize, terminal};
+// these are traits implemented on str so we need to bring them into scope to call e.g. width()
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::models::{SortDirection, SortKey, SortOrder};
+use crate::ui::{get_reset_string, get_theme};
+
+// used when the terminal size can't be read e.g. when output is piped
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+const DEFAULT_TERMINAL_HEIGHT: usize = 24;
+
+// Splits text into runs of printable text and ANSI escape sequences (the second item is true for
+// escape sequences), so styled text can be measured and cut without counting or splitting the
+// sequences. Only CSI sequences (ESC [ ... final byte) are recognised, which covers everything
+// the theme produces
+fn split_escape_sequences(text: &str) -> Vec<(&str, bool)> {
+    let mut parts = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b[") {
+        if start > 0 {
+            parts.push((&rest[..start], false));
+        }
+        let sequence_len = rest[start + 2..]
+            .find(|c: char| ('@'..='~').contains(&c))
+            .map_or(rest.len() - start, |end| end + 3);
+        parts.push((&rest[start..start + sequence_len], true));
+        rest = &rest[start + sequence_len..];
+    }
+    if !rest.is_empty() {
+        parts.push((rest, false));
+    }
+    parts
+}
+
+// number of terminal columns the text takes up. Wide characters such as CJK and most emoji take
+// two columns, combining marks and escape sequences take none, so this differs from both len()
+// and chars().count()
+pub fn get_display_width(text: &str) -> usize {
+    split_escape_sequences(text)
+        .into_iter()
+        .filter(|(_, is_escape)| !is_escape)
+        .map(|(part, _)| part.width())
+        .sum()
+}
 
 pub fn get_column_string(text: &str, width: usize) -> String {
-    let text_len = text.len();
-    if text_len <= width {
+    let text_width = get_display_width(text);
+    if text_width <= width {
         // need to pad the truncated string with spaces or UI will get wonky
-        let pads = width - text_len;
-        let mut padded = text.to_owned();
-        for _ in 0..pads {
-            padded.push(' ');
-        }
-        // println!("padded length: {}", padded.len());
-        return padded;
+        return format!("{}{}", text, " ".repeat(width - text_width));
     }
-    // truncate_ellipse() trims to specified length and always appends ... so result will be
-    // too long for width values < 4
+    // the ellipsis always takes 3 columns so there is no room for any text when width < 4
     match width {
         0 => "".to_string(),
         1 => ".".to_string(),
         2 => "..".to_string(),
         3 => "...".to_string(),
-        _ => text.truncate_ellipse(width - 3).to_string(),
+        _ => {
+            // cut on grapheme boundaries so accents and emoji sequences are never split. Escape
+            // sequences are kept whole and take no space
+            let mut truncated = String::new();
+            let mut truncated_width = 0;
+            let mut styled = false;
+            'parts: for (part, is_escape) in split_escape_sequences(text) {
+                if is_escape {
+                    truncated.push_str(part);
+                    styled = true;
+                    continue;
+                }
+                for grapheme in part.graphemes(true) {
+                    let grapheme_width = grapheme.width();
+                    if truncated_width + grapheme_width > width - 3 {
+                        break 'parts;
+                    }
+                    truncated.push_str(grapheme);
+                    truncated_width += grapheme_width;
+                }
+            }
+            // the cut may have dropped the sequence that switches the style back off
+            if styled {
+                truncated.push_str(&get_reset_string());
+            }
+            // a double width character that didn't fit leaves a one column gap to pad
+            format!(
+                "{}...{}",
+                truncated,
+                " ".repeat(width - 3 - truncated_width)
+            )
+        }
+    }
+}
+
+// centres the text, giving any odd padding to the left, to match the existing table headers
+pub fn get_header_string(text: &str, width: usize) -> String {
+    let text_len = get_display_width(text);
+    if text_len >= width {
+        return get_column_string(text, width);
+    }
+    let right_pads = (width - text_len) / 2;
+    let left_pads = width - text_len - right_pads;
+    format!(
+        "{}{}{}",
+        " ".repeat(left_pads),
+        text,
+        " ".repeat(right_pads)
+    )
+}
+
+// header text for a column the list can be sorted by. The column currently sorted on is marked
+// with ^ when ascending and v when descending
+pub fn get_sortable_header(text: &str, key: SortKey, sort_order: &SortOrder) -> String {
+    if sort_order.key != key {
+        return text.to_owned();
+    }
+    let marker = match sort_order.direction {
+        SortDirection::Ascending => "^",
+        SortDirection::Descending => "v",
+    };
+    format!("{} {}", text, marker)
+}
+
+// number of list rows that fit on screen once the lines a page prints around the list (headers,
+// footers, command bar and the input line) are taken away. Always at least one row
+pub fn get_page_size(reserved_lines: usize) -> usize {
+    let height = terminal::size()
+        .map(|(_, rows)| rows as usize)
+        .unwrap_or(DEFAULT_TERMINAL_HEIGHT);
+    height.saturating_sub(reserved_lines).max(1)
+}
+
+pub fn get_terminal_width() -> usize {
+    terminal::size()
+        .map(|(columns, _)| columns as usize)
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+// Tracks which page of a list is shown and, in the raw-mode TUI, which row is highlighted. Lives
+// on the page object so the position is kept while the page sits on the navigator's stack, e.g.
+// when returning to it via NavigateToPreviousPage. Interior mutability is needed because pages
+// are only ever borrowed immutably
+#[derive(Default)]
+pub struct Pager {
+    current_page: Cell<usize>,
+    // index into the whole list, not the current page. None until the TUI turns highlighting on
+    selected: Cell<Option<usize>>,
+    // page size used by the last call to get_page(), so the selection can move between pages
+    page_size: Cell<usize>,
+}
+
+impl Pager {
+    // returns the items on the current page. Clamps the current page first, as the list may
+    // have shrunk (e.g. after a delete) or the terminal may have been resized. The highlighted row
+    // is kept on the current page, moving to its first row after the page changes
+    pub fn get_page<'a, T>(&self, items: &'a [T], page_size: usize) -> &'a [T] {
+        let page_size = page_size.max(1);
+        self.page_size.set(page_size);
+
+        let page_count = Self::get_page_count(items.len(), page_size);
+        let current_page = self.current_page.get().min(page_count - 1);
+        self.current_page.set(current_page);
+
+        let start = current_page * page_size;
+        let end = (start + page_size).min(items.len());
+
+        if let Some(selected) = self.selected.get() {
+            if selected >= items.len() && end > start {
+                self.selected.set(Some(end - 1));
+            } else if selected < start || selected >= end {
+                self.selected.set(Some(start));
+            }
+        }
+
+        &items[start..end]
+    }
+
+    // moving past the last page is clamped the next time get_page() is called
+    pub fn next_page(&self) {
+        self.current_page.set(self.current_page.get() + 1);
+    }
+
+    pub fn previous_page(&self) {
+        self.current_page
+            .set(self.current_page.get().saturating_sub(1));
+    }
+
+    // Moves the highlight by offset rows, turning page when it moves off the current one. An
+    // offset of 0 just turns highlighting on. Moving past the end of the list is clamped the next
+    // time get_page() is called
+    pub fn move_selection(&self, offset: isize) {
+        let page_size = self.page_size.get().max(1);
+        let selected = match self.selected.get() {
+            Some(selected) => selected.saturating_add_signed(offset),
+            None => self.current_page.get() * page_size,
+        };
+        self.selected.set(Some(selected));
+        self.current_page.set(selected / page_size);
+    }
+
+    // index of the highlighted row in the whole list
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected.get()
+    }
+
+    // whether the row at this index on the current page is highlighted
+    pub fn is_selected(&self, row: usize) -> bool {
+        let start = self.current_page.get() * self.page_size.get();
+        self.selected.get() == Some(start + row)
+    }
+
+    #[cfg(test)]
+    pub fn get_current_page(&self) -> usize {
+        self.current_page.get()
+    }
+
+    pub fn get_footer(&self, item_count: usize, page_size: usize) -> String {
+        format!(
+            "page {} of {}, {} items",
+            self.current_page.get() + 1,
+            Self::get_page_count(item_count, page_size),
+            item_count
+        )
+    }
+
+    // an empty list still has one (empty) page
+    fn get_page_count(item_count: usize, page_size: usize) -> usize {
+        item_count.div_ceil(page_size.max(1)).max(1)
+    }
+}
+
+// the list of commands printed at the bottom of every page
+pub fn get_command_bar_string(commands: &str) -> String {
+    let theme = get_theme();
+    theme.paint(commands, &theme.command_bar)
+}
+
+// shows the row in reverse video when it is the highlighted row
+pub fn get_row_string(row: String, highlighted: bool) -> String {
+    if highlighted {
+        row.reverse().to_string()
+    } else {
+        row
     }
 }
 
@@ -57,4 +277,149 @@ mod tests {
         assert_eq!(get_column_string(text3, width), "testme".to_owned());
         assert_eq!(get_column_string(text4, width), "tes...".to_owned());
     }
+
+    #[test]
+    fn test_get_column_string_with_mixed_scripts() {
+        // accents, both precomposed and as combining marks, take one column
+        assert_eq!(get_column_string("Café", 6), "Café  ");
+        assert_eq!(get_column_string("Cafe\u{301}", 6), "Cafe\u{301}  ");
+        assert_eq!(get_column_string("Cafe\u{301} crème", 8), "Cafe\u{301} ...");
+        assert_eq!(get_column_string("Cafe\u{301} crème", 7), "Cafe\u{301}...");
+
+        // CJK characters take two columns
+        assert_eq!(get_column_string("日本語", 8), "日本語  ");
+        assert_eq!(get_column_string("日本語テキスト", 8), "日本... ");
+        assert_eq!(get_column_string("日本語テキスト", 9), "日本語...");
+        assert_eq!(get_column_string("名前 name", 8), "名前 ...");
+
+        // emoji, including multi codepoint sequences, are kept whole
+        assert_eq!(get_column_string("🍌 banana", 10), "🍌 banana ");
+        assert_eq!(get_column_string("👩‍🚀👩‍🚀 crew", 8), "👩‍🚀👩‍🚀 ...");
+        assert_eq!(get_column_string("🇬🇧 flag", 5), "🇬🇧...");
+
+        // every result is exactly the requested width
+        for text in ["Ünïcödé", "日本語テキスト", "🍌🍌🍌🍌", "mixed 日本 🍌 é"]
+        {
+            for width in 0..12 {
+                assert_eq!(get_display_width(&get_column_string(text, width)), width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_column_string_with_escape_sequences() {
+        let red = "\x1b[38;5;9m";
+        let reset = "\x1b[39m";
+        let styled = format!("{}OPEN{}", red, reset);
+
+        assert_eq!(get_display_width(&styled), 4);
+        assert_eq!(get_column_string(&styled, 6), format!("{}  ", styled));
+        assert_eq!(
+            get_column_string(&format!("{}RESOLVED{}", red, reset), 7),
+            format!("{}RESO{}...", red, get_reset_string())
+        );
+        assert_eq!(get_header_string(&styled, 8), format!("  {}  ", styled));
+
+        // styled wide characters
+        let styled = format!("{}日本語{}テキスト", red, reset);
+        assert_eq!(get_display_width(&styled), 14);
+        assert_eq!(
+            get_column_string(&styled, 10),
+            format!("{}日本語{}{}... ", red, reset, get_reset_string())
+        );
+    }
+
+    #[test]
+    fn test_get_header_string() {
+        assert_eq!(get_header_string("id", 12), "     id     ");
+        assert_eq!(get_header_string("status", 17), "      status     ");
+        assert_eq!(get_header_string("testmetest", 4), "t...");
+
+        let sort_order = SortOrder {
+            key: SortKey::Name,
+            direction: SortDirection::Descending,
+        };
+        assert_eq!(get_sortable_header("id", SortKey::Id, &sort_order), "id");
+        assert_eq!(
+            get_sortable_header("name", SortKey::Name, &sort_order),
+            "name v"
+        );
+    }
+
+    #[test]
+    fn test_pager() {
+        let items: Vec<u32> = (1..=7).collect();
+        let pager = Pager::default();
+
+        assert_eq!(pager.get_page(&items, 3), &[1, 2, 3]);
+        assert_eq!(pager.get_footer(items.len(), 3), "page 1 of 3, 7 items");
+
+        pager.previous_page();
+        assert_eq!(pager.get_page(&items, 3), &[1, 2, 3]);
+
+        pager.next_page();
+        pager.next_page();
+        assert_eq!(pager.get_page(&items, 3), &[7]);
+        assert_eq!(pager.get_footer(items.len(), 3), "page 3 of 3, 7 items");
+
+        pager.next_page();
+        assert_eq!(pager.get_page(&items, 3), &[7]);
+
+        pager.previous_page();
+        assert_eq!(pager.get_page(&items, 3), &[4, 5, 6]);
+
+        // list shrinks so the current page no longer exists
+        pager.next_page();
+        assert_eq!(pager.get_page(&items[..2], 3), &[1, 2]);
+        assert_eq!(pager.get_current_page(), 0);
+
+        let empty: Vec<u32> = vec![];
+        assert_eq!(pager.get_page(&empty, 3), &[] as &[u32]);
+        assert_eq!(pager.get_footer(empty.len(), 3), "page 1 of 1, 0 items");
+    }
+
+    #[test]
+    fn test_pager_selection() {
+        let items: Vec<u32> = (1..=7).collect();
+        let pager = Pager::default();
+
+        assert_eq!(pager.get_page(&items, 3), &[1, 2, 3]);
+        assert_eq!(pager.get_selected(), None);
+        assert!(!pager.is_selected(0));
+
+        // highlighting starts on the first row of the current page
+        pager.next_page();
+        pager.get_page(&items, 3);
+        pager.move_selection(0);
+        assert_eq!(pager.get_selected(), Some(3));
+        assert!(pager.is_selected(0));
+
+        // moving off the page turns the page
+        pager.move_selection(-1);
+        assert_eq!(pager.get_page(&items, 3), &[1, 2, 3]);
+        assert_eq!(pager.get_selected(), Some(2));
+        assert!(pager.is_selected(2));
+
+        pager.move_selection(1);
+        assert_eq!(pager.get_page(&items, 3), &[4, 5, 6]);
+        assert_eq!(pager.get_selected(), Some(3));
+
+        // moving past either end of the list is clamped
+        pager.move_selection(10);
+        assert_eq!(pager.get_page(&items, 3), &[7]);
+        assert_eq!(pager.get_selected(), Some(6));
+        pager.move_selection(-10);
+        assert_eq!(pager.get_page(&items, 3), &[1, 2, 3]);
+        assert_eq!(pager.get_selected(), Some(0));
+
+        // changing page moves the highlight onto the new page
+        pager.next_page();
+        assert_eq!(pager.get_page(&items, 3), &[4, 5, 6]);
+        assert_eq!(pager.get_selected(), Some(3));
+
+        // the list shrinks under the highlighted row
+        pager.move_selection(2);
+        assert_eq!(pager.get_page(&items[..4], 3), &[4]);
+        assert_eq!(pager.get_selected(), Some(3));
+    }
 }
@@ -0,0 +1,189 @@
+use itertools::Itertools;
+
+use crate::ui::get_theme;
+
+use super::page_helpers::{
+    get_column_string, get_display_width, get_header_string, get_terminal_width,
+};
+
+const SEPARATOR: &str = "|";
+
+// Describes one column of a table. Every column gets at least min_width; whatever terminal width
+// is left over is shared out between the columns in proportion to their weight, up to max_width.
+// A weight of 0 keeps the column at its minimum width
+pub struct ColumnSpec {
+    pub header: String,
+    pub min_width: usize,
+    pub max_width: usize,
+    pub weight: usize,
+}
+
+impl ColumnSpec {
+    pub fn new(
+        header: impl Into<String>,
+        min_width: usize,
+        max_width: usize,
+        weight: usize,
+    ) -> Self {
+        Self {
+            header: header.into(),
+            min_width,
+            max_width: max_width.max(min_width),
+            weight,
+        }
+    }
+}
+
+pub struct Table {
+    columns: Vec<ColumnSpec>,
+    widths: Vec<usize>,
+}
+
+impl Table {
+    // lays the columns out across the current terminal width
+    pub fn new(columns: Vec<ColumnSpec>) -> Self {
+        Self::with_width(columns, get_terminal_width())
+    }
+
+    pub fn with_width(columns: Vec<ColumnSpec>, width: usize) -> Self {
+        let separators = columns.len().saturating_sub(1) * SEPARATOR.len();
+        let widths = distribute_width(&columns, width.saturating_sub(separators));
+        Self { columns, widths }
+    }
+
+    // width of a full row including separators
+    pub fn get_width(&self) -> usize {
+        self.widths.iter().sum::<usize>() + self.widths.len().saturating_sub(1) * SEPARATOR.len()
+    }
+
+    // section title centred in a line of dashes spanning the table e.g. "----- EPICS -----"
+    pub fn get_title_string(&self, title: &str) -> String {
+        let title = format!(" {} ", title);
+        let title_width = get_display_width(&title);
+        let width = self.get_width();
+        let title = if title_width >= width {
+            title.trim().to_owned()
+        } else {
+            let right = (width - title_width) / 2;
+            let left = width - title_width - right;
+            format!("{}{}{}", "-".repeat(left), title, "-".repeat(right))
+        };
+        let theme = get_theme();
+        theme.paint(&title, &theme.title)
+    }
+
+    pub fn get_header_string(&self) -> String {
+        let header = self
+            .columns
+            .iter()
+            .zip(&self.widths)
+            .map(|(column, width)| get_header_string(&column.header, *width))
+            .join(SEPARATOR);
+        let theme = get_theme();
+        theme.paint(&header, &theme.header)
+    }
+
+    // missing cells are left blank and extra cells are ignored
+    pub fn get_row_string(&self, cells: &[&str]) -> String {
+        self.widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| get_column_string(cells.get(i).copied().unwrap_or(""), *width))
+            .join(SEPARATOR)
+    }
+}
+
+// Starts every column at its minimum width then repeatedly shares the remaining width between the
+// columns that can still grow, by weight. Integer division can leave a few columns undistributed,
+// those are handed out one at a time in column order. If the minimum widths don't fit they are
+// used anyway and the rows will be wider than the terminal
+fn distribute_width(columns: &[ColumnSpec], available: usize) -> Vec<usize> {
+    let mut widths: Vec<usize> = columns.iter().map(|column| column.min_width).collect();
+    let mut remaining = available.saturating_sub(widths.iter().sum());
+
+    loop {
+        let growable: Vec<usize> = (0..columns.len())
+            .filter(|i| columns[*i].weight > 0 && widths[*i] < columns[*i].max_width)
+            .collect();
+        if remaining == 0 || growable.is_empty() {
+            break;
+        }
+
+        let total_weight: usize = growable.iter().map(|i| columns[*i].weight).sum();
+        let mut given = 0;
+        for i in &growable {
+            let share = remaining * columns[*i].weight / total_weight;
+            let grant = share.min(columns[*i].max_width - widths[*i]);
+            widths[*i] += grant;
+            given += grant;
+        }
+
+        if given == 0 {
+            for i in growable.into_iter().take(remaining) {
+                widths[i] += 1;
+                given += 1;
+            }
+        }
+        remaining -= given;
+    }
+
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_columns() -> Vec<ColumnSpec> {
+        vec![
+            ColumnSpec::new("id", 4, 12, 1),
+            ColumnSpec::new("name", 10, usize::MAX, 4),
+            ColumnSpec::new("status", 11, 17, 1),
+        ]
+    }
+
+    #[test]
+    fn distribute_width_should_use_min_widths_when_too_narrow() {
+        let widths = distribute_width(&get_columns(), 10);
+        assert_eq!(widths, vec![4, 10, 11]);
+    }
+
+    #[test]
+    fn distribute_width_should_share_by_weight() {
+        let widths = distribute_width(&get_columns(), 63);
+        assert_eq!(widths.iter().sum::<usize>(), 63);
+        assert_eq!(widths, vec![11, 35, 17]);
+    }
+
+    #[test]
+    fn distribute_width_should_respect_max_widths() {
+        let widths = distribute_width(&get_columns(), 200);
+        assert_eq!(widths, vec![12, 171, 17]);
+
+        let columns = vec![ColumnSpec::new("id", 2, 4, 1), ColumnSpec::new("", 2, 4, 0)];
+        assert_eq!(distribute_width(&columns, 200), vec![4, 2]);
+    }
+
+    #[test]
+    fn table_should_render_rows_at_full_width() {
+        let table = Table::with_width(get_columns(), 65);
+
+        assert_eq!(table.get_width(), 65);
+        assert_eq!(
+            table.get_title_string("EPICS"),
+            "----------------------------- EPICS -----------------------------"
+        );
+        assert_eq!(
+            table.get_header_string(),
+            "     id    |                name               |      status     "
+        );
+        assert_eq!(
+            table.get_row_string(&["1", "Epic", "OPEN"]),
+            "1          |Epic                               |OPEN             "
+        );
+        assert_eq!(
+            table.get_row_string(&["1"]),
+            "1          |                                   |                 "
+        );
+    }
+}
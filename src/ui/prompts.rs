@@ -1,72 +1,270 @@
+use anyhow::Result;
+use chrono::{Days, NaiveDate};
+
 use crate::{
-    io_utils::get_user_input,
-    models::{Epic, Status, Story},
+    due_date::{parse_due_date, today, DUE_DATE_HELP},
+    io_utils::{get_user_input, wait_for_key_press},
+    models::{Epic, LinkKind, Project, Sprint, Status, Story, Worklog},
+    time_tracking::{current_user, parse_duration, parse_work_date, DURATION_HELP, WORK_DATE_HELP},
 };
 
+use super::editor::{edit_item, get_editor, ItemText};
+
+// takes the current item, or a blank one when creating, and returns None if the edit was cancelled
+type EditPrompt<T> = Box<dyn Fn(&T) -> Option<T>>;
+
+// None when cancelled, Some(None) to remove the due date
+type DueDatePrompt = Box<dyn Fn() -> Option<Option<NaiveDate>>>;
+
+// takes the number of unfinished stories and the name of the sprint they can move to. None when
+// cancelled, otherwise whether to carry the stories over
+type CloseSprintPrompt = Box<dyn Fn(usize, Option<&str>) -> Option<bool>>;
+
+// takes the id of the story to log time on, None when cancelled
+type LogWorkPrompt = Box<dyn Fn(u32) -> Option<Worklog>>;
+
+// takes the ids of the unfinished stories blocking the story, whether to start it anyway
+type StartBlockedStoryPrompt = Box<dyn Fn(&[u32]) -> bool>;
+
+// sprints are two weeks unless an end date is given
+const SPRINT_LENGTH: Days = Days::new(14);
+
 pub struct Prompts {
+    pub create_project: Box<dyn Fn() -> Project>,
+    // asks for the id of the project to move an epic to
+    pub select_project: Box<dyn Fn() -> Option<u32>>,
     pub create_epic: Box<dyn Fn() -> Epic>,
     pub create_story: Box<dyn Fn() -> Story>,
     pub delete_epic: Box<dyn Fn() -> bool>,
     pub delete_story: Box<dyn Fn() -> bool>,
     pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub update_due_date: DueDatePrompt,
+    pub create_sprint: Box<dyn Fn() -> Sprint>,
+    // asks for the id of a story to add to or remove from a sprint
+    pub select_story: Box<dyn Fn() -> Option<u32>>,
+    pub close_sprint: CloseSprintPrompt,
+    pub log_work: LogWorkPrompt,
+    // asks how to link a story and the id of the other story
+    pub link_story: Box<dyn Fn() -> Option<(LinkKind, u32)>>,
+    pub start_blocked_story: StartBlockedStoryPrompt,
+    // opens the item in the user's editor, used both to create and to update items
+    pub edit_epic: EditPrompt<Epic>,
+    pub edit_story: EditPrompt<Story>,
 }
 
 impl Prompts {
     pub fn new() -> Self {
         Self {
+            create_project: Box::new(create_project_prompt),
+            select_project: Box::new(select_project_prompt),
             create_epic: Box::new(create_epic_prompt),
             create_story: Box::new(create_story_prompt),
             delete_epic: Box::new(delete_epic_prompt),
             delete_story: Box::new(delete_story_prompt),
             update_status: Box::new(update_status_prompt),
+            update_due_date: Box::new(update_due_date_prompt),
+            create_sprint: Box::new(create_sprint_prompt),
+            select_story: Box::new(select_story_prompt),
+            close_sprint: Box::new(close_sprint_prompt),
+            log_work: Box::new(log_work_prompt),
+            link_story: Box::new(link_story_prompt),
+            start_blocked_story: Box::new(start_blocked_story_prompt),
+            edit_epic: Box::new(edit_epic_prompt),
+            edit_story: Box::new(edit_story_prompt),
         }
     }
 }
 
+fn create_project_prompt() -> Project {
+    println!("----------------------------");
+    println!("Project Name:");
+    let name = get_user_input();
+    println!("Project Key (a short code such as WEB):");
+    let key = get_user_input();
+    Project::new(name.trim().to_owned(), key.trim().to_owned())
+}
+
+fn select_project_prompt() -> Option<u32> {
+    println!("----------------------------");
+    println!("Project Id:");
+    let input = get_user_input();
+    input.trim().parse().ok()
+}
+
 fn create_epic_prompt() -> Epic {
     println!("----------------------------");
     println!("Epic Name:");
-    let mut name = get_user_input();
+    let name = get_user_input();
     println!("Epic Description:");
-    let mut description = get_user_input();
-    let epic = Epic::new(name.trim().to_owned(), description.trim().to_owned());
+    let description = get_user_input();
+    let mut epic = Epic::new(name.trim().to_owned(), description.trim().to_owned());
+    epic.due_date = read_due_date();
     epic
 }
 
 fn create_story_prompt() -> Story {
     println!("----------------------------");
     println!("Story Name:");
-    let mut name = get_user_input();
+    let name = get_user_input();
     println!("Story Description:");
-    let mut description = get_user_input();
-    let story = Story::new(name.trim().to_owned(), description.trim().to_owned());
+    let description = get_user_input();
+    let mut story = Story::new(name.trim().to_owned(), description.trim().to_owned());
+    story.due_date = read_due_date();
     story
 }
 
-fn delete_epic_prompt() -> bool {
+fn read_due_date() -> Option<NaiveDate> {
+    println!("Due Date ({}, blank for none):", DUE_DATE_HELP);
+    read_date(parse_due_date)
+}
+
+// asks until parse accepts the input or it is blank for none
+fn read_date(parse: fn(&str, NaiveDate) -> Result<NaiveDate>) -> Option<NaiveDate> {
+    loop {
+        let input = get_user_input();
+        if input.trim().is_empty() {
+            return None;
+        }
+        match parse(&input, today()) {
+            Ok(date) => return Some(date),
+            Err(e) => println!("{}, try again:", e),
+        }
+    }
+}
+
+fn create_sprint_prompt() -> Sprint {
     println!("----------------------------");
-    println!("Are you sure you want to delete this Epic? All stories in this Epic will also be deleted [Y/n]:");
-    let mut input = get_user_input();
+    println!("Sprint Name:");
+    let name = get_user_input();
+    println!("Sprint Goal:");
+    let goal = get_user_input();
+    println!("Start Date ({}, blank for today):", DUE_DATE_HELP);
+    let start_date = read_date(parse_due_date).unwrap_or_else(today);
+    let default_end_date = start_date
+        .checked_add_days(SPRINT_LENGTH)
+        .unwrap_or(start_date);
+    println!(
+        "End Date ({}, blank for {}):",
+        DUE_DATE_HELP, default_end_date
+    );
+    let end_date = loop {
+        let end_date = read_date(parse_due_date).unwrap_or(default_end_date);
+        if end_date >= start_date {
+            break end_date;
+        }
+        println!("the end date can't be before the start date, try again:");
+    };
+    Sprint::new(
+        name.trim().to_owned(),
+        goal.trim().to_owned(),
+        start_date,
+        end_date,
+    )
+}
+
+fn select_story_prompt() -> Option<u32> {
+    println!("----------------------------");
+    println!("Story Id:");
+    let input = get_user_input();
+    input.trim().parse().ok()
+}
+
+fn close_sprint_prompt(unfinished: usize, next_sprint: Option<&str>) -> Option<bool> {
+    println!("----------------------------");
+    let Some(next_sprint) = next_sprint.filter(|_| unfinished > 0) else {
+        println!("Are you sure you want to close this Sprint? [Y/n]:");
+        let input = get_user_input();
+        return matches!(input.trim(), "Y").then_some(false);
+    };
+    println!(
+        "Carry the {} unfinished stories into {}? [Y]es, [n]o or [c]ancel:",
+        unfinished, next_sprint
+    );
+    let input = get_user_input();
     match input.trim() {
-        "Y" => true,
-        _ => false,
+        "Y" => Some(true),
+        "n" => Some(false),
+        _ => None,
     }
 }
 
+fn log_work_prompt(story_id: u32) -> Option<Worklog> {
+    println!("----------------------------");
+    println!("Time Spent ({}, blank to cancel):", DURATION_HELP);
+    let minutes = loop {
+        let input = get_user_input();
+        if input.trim().is_empty() {
+            return None;
+        }
+        match parse_duration(&input) {
+            Ok(minutes) => break minutes,
+            Err(e) => println!("{}, try again:", e),
+        }
+    };
+    println!("Date ({}, blank for today):", WORK_DATE_HELP);
+    let date = read_date(parse_work_date).unwrap_or_else(today);
+    println!("Note:");
+    let note = get_user_input();
+    Some(Worklog {
+        story_id,
+        user: current_user(),
+        date,
+        minutes,
+        note: note.trim().to_owned(),
+        story_name: String::new(),
+    })
+}
+
+fn link_story_prompt() -> Option<(LinkKind, u32)> {
+    println!("----------------------------");
+    println!("Link (1 - BLOCKS, 2 - BLOCKED BY, 3 - RELATES TO):");
+    let kind = match get_user_input().trim() {
+        "1" => LinkKind::Blocks,
+        "2" => LinkKind::BlockedBy,
+        "3" => LinkKind::RelatesTo,
+        _ => return None,
+    };
+    println!("Story Id:");
+    let input = get_user_input();
+    Some((kind, input.trim().parse().ok()?))
+}
+
+fn start_blocked_story_prompt(blockers: &[u32]) -> bool {
+    println!("----------------------------");
+    let ids: Vec<String> = blockers.iter().map(|id| id.to_string()).collect();
+    let (stories, verb) = if ids.len() == 1 {
+        ("story", "isn't")
+    } else {
+        ("stories", "aren't")
+    };
+    println!(
+        "WARNING: this Story is blocked by {} {}, which {} done yet. Move it to IN PROGRESS anyway? [Y/n]:",
+        stories,
+        ids.join(", "),
+        verb
+    );
+    let input = get_user_input();
+    matches!(input.trim(), "Y")
+}
+
+fn delete_epic_prompt() -> bool {
+    println!("----------------------------");
+    println!("Are you sure you want to delete this Epic? All stories in this Epic will also be deleted [Y/n]:");
+    let input = get_user_input();
+    matches!(input.trim(), "Y")
+}
+
 fn delete_story_prompt() -> bool {
     println!("----------------------------");
     println!("Are you sure you want to delete this Story? [Y/n]:");
-    let mut input = get_user_input();
-    match input.trim() {
-        "Y" => true,
-        _ => false,
-    }
+    let input = get_user_input();
+    matches!(input.trim(), "Y")
 }
 
 fn update_status_prompt() -> Option<Status> {
     println!("----------------------------");
     println!("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED):");
-    let mut input = get_user_input();
+    let input = get_user_input();
     if let Ok(valid_int) = input.trim().parse::<i32>() {
         return match valid_int {
             1 => Some(Status::Open),
@@ -78,3 +276,44 @@ fn update_status_prompt() -> Option<Status> {
     }
     None
 }
+
+// asks until the input is a valid date, "-" removes the due date and blank leaves it as it is
+fn update_due_date_prompt() -> Option<Option<NaiveDate>> {
+    println!("----------------------------");
+    println!(
+        "New Due Date ({}, - to remove, blank to cancel):",
+        DUE_DATE_HELP
+    );
+    loop {
+        let input = get_user_input();
+        match input.trim() {
+            "" => return None,
+            "-" => return Some(None),
+            input => match parse_due_date(input, today()) {
+                Ok(due_date) => return Some(Some(due_date)),
+                Err(e) => println!("{}, try again:", e),
+            },
+        }
+    }
+}
+
+fn edit_epic_prompt(epic: &Epic) -> Option<Epic> {
+    let edited = edit_in_editor(&ItemText::from_epic(epic))?;
+    Some(edited.apply_to_epic(epic.clone()))
+}
+
+fn edit_story_prompt(story: &Story) -> Option<Story> {
+    let edited = edit_in_editor(&ItemText::from_story(story))?;
+    Some(edited.apply_to_story(story.clone()))
+}
+
+fn edit_in_editor(item: &ItemText) -> Option<ItemText> {
+    match edit_item(&get_editor(), item) {
+        Ok(edited) => edited,
+        Err(error) => {
+            println!("ERROR: failed to edit item: {}", error);
+            wait_for_key_press();
+            None
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ListItem, Status};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+// Colours and attributes for one element of the UI. Colours use crossterm's names e.g. "red",
+// "dark_grey", "ansi_(208)" or "rgb_(255,128,0)"
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    fn with_foreground(color: Color) -> Self {
+        Self {
+            foreground: Some(color),
+            ..Default::default()
+        }
+    }
+
+    fn get_content_style(&self) -> ContentStyle {
+        let mut style = ContentStyle::new();
+        style.foreground_color = self.foreground;
+        style.background_color = self.background;
+        if self.bold {
+            style = style.bold();
+        }
+        if self.underline {
+            style = style.underlined();
+        }
+        style
+    }
+}
+
+// Styles for the parts of every page. Any element missing from a theme file keeps its default
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(default)]
+pub struct Theme {
+    pub title: Style,
+    pub header: Style,
+    pub command_bar: Style,
+    pub open: Style,
+    pub in_progress: Style,
+    pub resolved: Style,
+    pub closed: Style,
+    // due dates that have passed on items that aren't done
+    pub overdue: Style,
+    // stories waiting on a story that isn't done
+    pub blocked: Style,
+    // decided by the environment rather than the theme file, see should_use_colour()
+    #[serde(skip)]
+    pub colour: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Style {
+                bold: true,
+                ..Default::default()
+            },
+            header: Style {
+                bold: true,
+                underline: true,
+                ..Default::default()
+            },
+            command_bar: Style::with_foreground(Color::DarkCyan),
+            open: Style::with_foreground(Color::Blue),
+            in_progress: Style::with_foreground(Color::Yellow),
+            resolved: Style::with_foreground(Color::Green),
+            closed: Style::with_foreground(Color::DarkGrey),
+            overdue: Style {
+                foreground: Some(Color::Red),
+                bold: true,
+                ..Default::default()
+            },
+            blocked: Style::with_foreground(Color::Red),
+            colour: false,
+        }
+    }
+}
+
+impl Theme {
+    // reads the theme file if there is one, otherwise uses the default theme
+    pub fn load(file_path: &str) -> Result<Self> {
+        let mut theme = if Path::new(file_path).exists() {
+            serde_json::from_str(&fs::read_to_string(file_path)?)?
+        } else {
+            Theme::default()
+        };
+        theme.colour = should_use_colour();
+        Ok(theme)
+    }
+
+    pub fn paint(&self, text: &str, style: &Style) -> String {
+        if !self.colour {
+            return text.to_owned();
+        }
+        style.get_content_style().apply(text).to_string()
+    }
+
+    pub fn paint_status(&self, status: &Status) -> String {
+        let style = match status {
+            Status::Open => &self.open,
+            Status::InProgress => &self.in_progress,
+            Status::Resolved => &self.resolved,
+            Status::Closed => &self.closed,
+        };
+        self.paint(&status.to_string(), style)
+    }
+
+    // the item's due date, overdue ones are marked with a "!" so they stand out without colour too
+    pub fn paint_due_date(&self, item: &(impl ListItem + ?Sized), today: NaiveDate) -> String {
+        match item.due_date() {
+            Some(due_date) if item.is_overdue(today) => {
+                self.paint(&format!("!{}", due_date), &self.overdue)
+            }
+            Some(due_date) => due_date.to_string(),
+            None => String::new(),
+        }
+    }
+
+    // the name of a story, blocked ones start with "[blocked]" so they stand out without colour too
+    pub fn paint_blocked(&self, name: &str, blocked: bool) -> String {
+        if !blocked {
+            return name.to_owned();
+        }
+        format!("{} {}", self.paint("[blocked]", &self.blocked), name)
+    }
+}
+
+// Colour is only used when writing to a terminal, and never when NO_COLOR is set to a non-empty
+// value (see https://no-color.org)
+pub fn should_use_colour() -> bool {
+    let no_colour = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    io::stdout().is_terminal() && !no_colour
+}
+
+// Sets the theme used by every page. Can only be set once, later calls are ignored
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+// the theme set at startup, or the default theme with colour turned off if there isn't one
+pub fn get_theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+// Reset sequence for anything a truncated string may have left switched on
+pub fn get_reset_string() -> String {
+    format!("{}", Attribute::Reset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Story;
+
+    #[test]
+    fn paint_should_only_style_when_colour_is_on() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.paint_status(&Status::Open), "OPEN");
+
+        theme.colour = true;
+        let painted = theme.paint_status(&Status::Open);
+        assert_ne!(painted, "OPEN");
+        assert!(painted.contains("OPEN"));
+        assert!(painted.starts_with('\x1b'));
+    }
+
+    #[test]
+    fn load_should_fill_missing_styles_with_defaults() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut tmpfile,
+            br#"{ "open": { "foreground": "magenta", "bold": true } }"#,
+        )
+        .unwrap();
+
+        let theme = Theme::load(tmpfile.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(
+            theme.open,
+            Style {
+                foreground: Some(Color::Magenta),
+                bold: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(theme.closed, Theme::default().closed);
+    }
+
+    #[test]
+    fn load_should_use_default_theme_without_a_file() {
+        let theme = Theme::load("INVALID_PATH").unwrap();
+        assert_eq!(theme.header, Theme::default().header);
+    }
+
+    #[test]
+    fn paint_due_date_should_mark_overdue_items() {
+        let theme = Theme::default();
+        let today = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        assert_eq!(theme.paint_due_date(&story, today), "");
+
+        story.due_date = NaiveDate::from_ymd_opt(2024, 2, 1);
+        assert_eq!(theme.paint_due_date(&story, today), "2024-02-01");
+
+        story.due_date = NaiveDate::from_ymd_opt(2024, 1, 31);
+        assert_eq!(theme.paint_due_date(&story, today), "!2024-01-31");
+
+        story.status = Status::Closed;
+        assert_eq!(theme.paint_due_date(&story, today), "2024-01-31");
+    }
+
+    #[test]
+    fn paint_blocked_should_mark_blocked_stories() {
+        let theme = Theme::default();
+        assert_eq!(theme.paint_blocked("Login", false), "Login");
+        assert_eq!(theme.paint_blocked("Login", true), "[blocked] Login");
+    }
+}